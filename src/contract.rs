@@ -1,13 +1,18 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
-use linera_edge::state::{EdgeState, AppInfo};
+use linera_edge::settlement::{self, AppSnapshot, SettlementSnapshot};
+use linera_edge::state::{AppInfo, EdgeState, SettlementRecord, SCHEMA_VERSION};
+use linera_edge::types::{mul_div, EdgeConfig, Role};
+use linera_edge::{
+    AppAddedEvent, AppRemovedEvent, BetPlacedEvent, EdgeError, EdgeEvent, EdgeResponse,
+    PoolInjectedEvent, RedeemedEvent, RegisteredEvent, EDGE_EVENTS_STREAM,
+};
 use linera_sdk::{Contract, ContractRuntime};
 use linera_sdk::abi::WithContractAbi;
-use linera_sdk::linera_base_types::{AccountOwner, Timestamp, StreamName, Amount};
+use linera_sdk::linera_base_types::{AccountOwner, Timestamp, TimeDelta, StreamName, Amount};
 use linera_sdk::views::RootView;
 
 use std::sync::Arc;
-use std::str::FromStr;
 use tokio::sync::Mutex;
 
 linera_sdk::contract!(EdgeContract);
@@ -20,8 +25,8 @@ pub struct EdgeContract {
 impl Contract for EdgeContract {
     type Message = ();
     type Parameters = ();
-    type InstantiationArgument = ();
-    type EventValue = ();
+    type InstantiationArgument = EdgeConfig;
+    type EventValue = EdgeEvent;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
         let context = runtime.root_view_storage_context();
@@ -37,395 +42,583 @@ impl Contract for EdgeContract {
         }
     }
 
-    async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
+    async fn instantiate(&mut self, config: Self::InstantiationArgument) {
         let mut state = self.state.lock().await;
         if state.owner.get().is_some() {
             return;
         }
+        
+        if let Err(reason) = config.validate() {
+            panic!("Invalid instantiation config: {reason}");
+        }
 
-        let contract_address: AccountOwner = self.runtime.application_id().into();
-        let _ = state.set_owner(contract_address).await;
+        let application_owner: AccountOwner = self.runtime.application_id().into();
+        let owner = self.runtime.authenticated_signer().unwrap_or(application_owner);
+        let _ = state.set_owner(owner).await;
+        let chain_owner = Self::chain_owner(&mut self.runtime);
+        let _ = state.propose_chain_owner(application_owner, chain_owner).await;
         
         let current_time = self.runtime.system_time();
         let _ = state.update_last_settle_time(current_time).await;
         
-        let _ = state.update_pool_amount(Amount::from_str("10000").unwrap()).await;
+        let _ = state.update_pool_amount(config.initial_pool).await;
+        let _ = state.record_mint(config.initial_pool).await;
+        let _ = state.set_config(config).await;
+        state.schema_version.set(SCHEMA_VERSION);
         
         let chain_id = self.runtime.chain_id();
         let application_id = self.runtime.application_id().forget_abi();
-        let stream_name = StreamName::from("edge_events");
+        let stream_name = StreamName::from(EDGE_EVENTS_STREAM);
         
         self.runtime.subscribe_to_events(chain_id, application_id, stream_name);
         
         let _ = state.save().await;
     }
 
-    async fn execute_operation(&mut self, operation: Self::Operation) {
+    async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        match self.dispatch_operation(operation).await {
+            Ok(response) => response,
+            Err(error) => panic!("Operation rejected: {error}"),
+        }
+    }
+
+    async fn execute_message(&mut self, _message: Self::Message) {}
+
+    async fn store(self) {
+        let mut state = self.state.lock().await;
+        let _ = state.save().await;
+    }
+}
+
+impl EdgeContract {
+    /// Returns the account acting on this operation: the block signer, or the calling
+    /// application when the operation comes from a cross-application call.
+    fn authenticated_caller(&mut self) -> Result<AccountOwner, EdgeError> {
+        if let Some(signer) = self.runtime.authenticated_signer() {
+            return Ok(signer);
+        }
+        match self.runtime.authenticated_caller_id() {
+            Some(caller_id) => Ok(caller_id.into()),
+            None => Err(EdgeError::Unauthenticated),
+        }
+    }
+
+    async fn dispatch_operation(&mut self, operation: linera_edge::EdgeOperation) -> Result<EdgeResponse, EdgeError> {
+        let caller = self.authenticated_caller()?;
+        self.migrate_state().await?;
+        self.apply_pending_config().await?;
+        
         match operation {
-            linera_edge::EdgeOperation::Bet { caller, app_id, amount } => {
-                self.handle_bet(caller, app_id, amount).await;
+            linera_edge::EdgeOperation::Register => {
+                self.handle_register(caller).await
+            }
+            linera_edge::EdgeOperation::Bet { app_id, amount } => {
+                self.handle_bet(caller, app_id, amount).await
+            }
+            linera_edge::EdgeOperation::Redeem { app_id, amount } => {
+                self.handle_redeem(caller, app_id, amount).await
+            }
+            linera_edge::EdgeOperation::Settle => {
+                self.handle_settle(caller).await
             }
-            linera_edge::EdgeOperation::Redeem { caller, app_id, amount } => {
-                self.handle_redeem(caller, app_id, amount).await;
+            linera_edge::EdgeOperation::AddApplication { app_id, name, description } => {
+                self.handle_add_application(caller, app_id, name, description).await
             }
-            linera_edge::EdgeOperation::Settle { caller } => {
-                self.handle_settle(caller).await;
+            linera_edge::EdgeOperation::RemoveApplication { app_id } => {
+                self.handle_remove_application(caller, app_id).await
             }
-            linera_edge::EdgeOperation::AddApplication { caller, app_id, name, description } => {
-                self.handle_add_application(caller, app_id, name, description).await;
+            linera_edge::EdgeOperation::InjectPool { amount } => {
+                self.handle_inject_pool(caller, amount).await
             }
-            linera_edge::EdgeOperation::RemoveApplication { caller, app_id } => {
-                self.handle_remove_application(caller, app_id).await;
+            linera_edge::EdgeOperation::UpdateConfig { config } => {
+                self.handle_update_config(caller, config).await
             }
-            linera_edge::EdgeOperation::InjectPool { caller, amount } => {
-                self.handle_inject_pool(caller, amount).await;
+            linera_edge::EdgeOperation::GrantRole { address, role } => {
+                self.handle_update_role(caller, address, role, true).await
+            }
+            linera_edge::EdgeOperation::RevokeRole { address, role } => {
+                self.handle_update_role(caller, address, role, false).await
+            }
+            linera_edge::EdgeOperation::ProposeOwnershipTransfer { new_owner } => {
+                self.handle_propose_ownership_transfer(caller, new_owner).await
+            }
+            linera_edge::EdgeOperation::AcceptOwnership => {
+                self.handle_accept_ownership(caller).await
             }
         }
     }
 
-    async fn execute_message(&mut self, _message: Self::Message) {}
+    async fn migrate_state(&mut self) -> Result<(), EdgeError> {
+        let mut state = self.state.lock().await;
+        if !state.needs_migration() {
+            return Ok(());
+        }
+        let application_owner = self.runtime.application_id().into();
+        let chain_owner = Self::chain_owner(&mut self.runtime);
+        state.migrate(application_owner, chain_owner).await?;
+        Ok(())
+    }
 
-    async fn store(self) {
+    /// The first super owner of this chain, or else its first regular owner.
+    fn chain_owner(runtime: &mut ContractRuntime<Self>) -> Option<AccountOwner> {
+        let ownership = runtime.chain_ownership();
+        ownership.super_owners.iter().chain(ownership.owners.keys()).next().copied()
+    }
+
+    async fn apply_pending_config(&mut self) -> Result<(), EdgeError> {
         let mut state = self.state.lock().await;
-        let _ = state.save().await;
+        let current_time = self.runtime.system_time();
+        state.apply_pending_config(current_time).await?;
+        Ok(())
     }
-}
 
-impl EdgeContract {
-    async fn handle_bet(&mut self, owner: AccountOwner, app_id: String, amount: Amount) {
+    async fn emit_event(&mut self, event: EdgeEvent) -> Result<(), EdgeError> {
+        let mut state = self.state.lock().await;
+        state.record_event(event.clone()).await?;
+        self.runtime.emit(StreamName::from(EDGE_EVENTS_STREAM), &event);
+        Ok(())
+    }
+
+    async fn ensure_owner(state: &EdgeState, caller: &AccountOwner) -> Result<(), EdgeError> {
+        if !state.is_owner(caller).await? {
+            return Err(EdgeError::NotOwner);
+        }
+        Ok(())
+    }
+
+    async fn ensure_role(state: &EdgeState, caller: &AccountOwner, role: Role) -> Result<(), EdgeError> {
+        if !state.has_role(caller, role).await? {
+            return Err(EdgeError::MissingRole(role));
+        }
+        Ok(())
+    }
+
+    async fn handle_register(&mut self, owner: AccountOwner) -> Result<EdgeResponse, EdgeError> {
+        let mut state = self.state.lock().await;
+        
+        if state.is_registered(&owner).await? {
+            return Err(EdgeError::AlreadyRegistered);
+        }
+        let balance = state.register_user(&owner).await?;
+        
+        state.save().await?;
+        drop(state);
+        
+        let current_time = self.runtime.system_time();
+        self.emit_event(EdgeEvent::Registered(RegisteredEvent {
+            owner,
+            grant: balance,
+            timestamp: current_time,
+        })).await?;
+        
+        Ok(EdgeResponse::Registered { balance })
+    }
+
+    async fn handle_bet(&mut self, owner: AccountOwner, app_id: String, amount: Amount) -> Result<EdgeResponse, EdgeError> {
         let mut state = self.state.lock().await;
         
         if amount <= Amount::ZERO {
-            return;
+            return Err(EdgeError::InvalidAmount);
+        }
+        let min_bet = state.get_config().await?.min_bet;
+        if amount < min_bet {
+            return Err(EdgeError::BetTooSmall { minimum: min_bet });
         }
         
-        let _ = state.initialize_user_balance(&owner).await;
+        match state.get_app_info(&app_id).await? {
+            None => return Err(EdgeError::UnknownApp(app_id)),
+            Some(app_info) if !app_info.is_active => return Err(EdgeError::AppInactive(app_id)),
+            Some(_) => {}
+        }
         
-        let current_balance = match state.get_user_balance(&owner).await {
-            Ok(balance) => balance,
-            Err(_) => return,
-        };
+        if !state.is_registered(&owner).await? {
+            return Err(EdgeError::NotRegistered);
+        }
         
+        let current_balance = state.get_user_balance(&owner).await?;
         if current_balance < amount {
-            return;
+            return Err(EdgeError::InsufficientBalance { available: current_balance, requested: amount });
         }
         
-        let current_bet = match state.get_user_app_bet(&owner, &app_id).await {
-            Ok(bet) => bet,
-            Err(_) => return,
-        };
-        
-        let max_bet = Amount::from_str("100").unwrap();
+        let current_bet = state.get_user_app_bet(&owner, &app_id).await?;
+        let max_bet = state.get_config().await?.max_bet_per_app;
         if current_bet.saturating_add(amount) > max_bet {
-            return;
+            return Err(EdgeError::BetCapExceeded { app_id, cap: max_bet });
         }
         
         let new_balance = current_balance.saturating_sub(amount);
-        let _ = state.update_user_balance(&owner, new_balance).await;
+        state.update_user_balance(&owner, new_balance).await?;
         
         let new_bet = current_bet.saturating_add(amount);
         let current_time = self.runtime.system_time();
-        let _ = state.update_user_bet(&owner, app_id.clone(), new_bet, current_time).await;
+        state.record_user_bet(&owner, &app_id, amount, current_time).await?;
         
-        let current_total = match state.get_app_total_bet(&app_id).await {
-            Ok(total) => total,
-            Err(_) => return,
-        };
-        let new_total = current_total.saturating_add(amount);
-        let _ = state.update_app_total_bet(app_id.clone(), new_total).await;
+        let current_total = state.get_app_total_bet(&app_id).await?;
+        state.update_app_total_bet(app_id.clone(), current_total.saturating_add(amount)).await?;
         
-        let current_app_contribution = match state.get_app_pool_contribution(&app_id).await {
-            Ok(contribution) => contribution,
-            Err(_) => Amount::ZERO,
-        };
-        let new_app_contribution = current_app_contribution.saturating_add(amount);
-        let _ = state.update_app_pool_contribution(app_id, new_app_contribution).await;
+        let current_app_contribution = state.get_app_pool_contribution(&app_id).await?;
+        state.update_app_pool_contribution(app_id.clone(), current_app_contribution.saturating_add(amount)).await?;
         
-        let current_pool = match state.get_pool_amount().await {
-            Ok(pool) => pool,
-            Err(_) => Amount::ZERO,
-        };
-        let new_pool = current_pool.saturating_add(amount);
-        let _ = state.update_pool_amount(new_pool).await;
+        state.save().await?;
+        drop(state);
         
-        let _ = state.save().await;
+        self.emit_event(EdgeEvent::BetPlaced(BetPlacedEvent {
+            owner,
+            app_id: app_id.clone(),
+            amount,
+            position: new_bet,
+            timestamp: current_time,
+        })).await?;
+        
+        Ok(EdgeResponse::BetPlaced { app_id, position: new_bet, balance: new_balance })
     }
 
-    async fn handle_redeem(&mut self, owner: AccountOwner, app_id: String, amount: Amount) {
+    async fn handle_redeem(&mut self, owner: AccountOwner, app_id: String, amount: Amount) -> Result<EdgeResponse, EdgeError> {
         let mut state = self.state.lock().await;
         
         if amount <= Amount::ZERO {
-            return;
+            return Err(EdgeError::InvalidAmount);
         }
         
-        let current_bet = match state.get_user_app_bet(&owner, &app_id).await {
-            Ok(bet) => bet,
-            Err(_) => return,
-        };
-        
+        let current_bet = state.get_user_app_bet(&owner, &app_id).await?;
         if amount > current_bet {
-            return;
+            return Err(EdgeError::InsufficientBet { app_id, available: current_bet, requested: amount });
         }
         
-        let hundred = Amount::from_str("100").unwrap();
-        let one = Amount::from_str("1").unwrap();
-        let fee = if amount <= hundred {
-            one
-        } else {
-            let fee_amount = amount.saturating_mul(1).saturating_div(100);
-            if fee_amount < one {
-                one
-            } else {
-                fee_amount
+        let config = state.get_config().await?;
+        let current_time = self.runtime.system_time();
+        
+        // Walk the lots in the order `redeem_user_bet` consumes them to find how much of
+        // the redemption is still locked.
+        let mut remaining = amount;
+        let mut locked = Amount::ZERO;
+        let mut unlock_at = current_time;
+        for bet in state.get_user_app_bets(&owner, &app_id).await? {
+            if remaining == Amount::ZERO {
+                break;
             }
-        };
-        let return_amount = amount.saturating_sub(fee);
+            let taken = std::cmp::min(bet.amount, remaining);
+            if bet.unlock_at > current_time {
+                locked = locked.saturating_add(taken);
+                unlock_at = std::cmp::max(unlock_at, bet.unlock_at);
+            }
+            remaining = remaining.saturating_sub(taken);
+        }
+        if locked > Amount::ZERO && config.early_exit_penalty_percent == 0 {
+            return Err(EdgeError::BetLocked { app_id, unlock_at });
+        }
         
-        let new_bet = current_bet.saturating_sub(amount);
-        let current_time = self.runtime.system_time();
-        let _ = state.update_user_bet(&owner, app_id.clone(), new_bet, current_time).await;
+        let fee = std::cmp::min(config.redeem_fee(amount), amount);
+        let penalty = Amount::from_attos(mul_div(u128::from(locked), config.early_exit_penalty_percent as u128, 100));
+        let penalty = std::cmp::min(penalty, amount.saturating_sub(fee));
+        let return_amount = amount.saturating_sub(fee).saturating_sub(penalty);
         
-        let current_balance = match state.get_user_balance(&owner).await {
-            Ok(balance) => balance,
-            Err(_) => return,
-        };
+        state.redeem_user_bet(&owner, &app_id, amount).await?;
+        
+        let current_balance = state.get_user_balance(&owner).await?;
         let new_balance = current_balance.saturating_add(return_amount);
-        let _ = state.update_user_balance(&owner, new_balance).await;
+        state.update_user_balance(&owner, new_balance).await?;
         
-        let current_total = match state.get_app_total_bet(&app_id).await {
-            Ok(total) => total,
-            Err(_) => return,
-        };
-        let new_total = current_total.saturating_sub(amount);
-        let _ = state.update_app_total_bet(app_id, new_total).await;
+        let current_total = state.get_app_total_bet(&app_id).await?;
+        state.update_app_total_bet(app_id.clone(), current_total.saturating_sub(amount)).await?;
         
-        let current_pool = match state.get_pool_amount().await {
-            Ok(pool) => pool,
-            Err(_) => return,
-        };
-        let new_pool = current_pool.saturating_add(fee);
-        let _ = state.update_pool_amount(new_pool).await;
+        let current_pool = state.get_pool_amount().await?;
+        state.update_pool_amount(current_pool.saturating_add(fee).saturating_add(penalty)).await?;
         
-        let _ = state.save().await;
+        state.save().await?;
+        drop(state);
+        
+        self.emit_event(EdgeEvent::Redeemed(RedeemedEvent {
+            owner,
+            app_id: app_id.clone(),
+            amount,
+            fee,
+            penalty,
+            timestamp: current_time,
+        })).await?;
+        
+        Ok(EdgeResponse::Redeemed { app_id, returned: return_amount, fee, penalty, balance: new_balance })
     }
 
-    async fn handle_add_application(&mut self, caller: AccountOwner, app_id: String, name: String, description: String) {
+    async fn handle_add_application(&mut self, caller: AccountOwner, app_id: String, name: String, description: String) -> Result<EdgeResponse, EdgeError> {
         let mut state = self.state.lock().await;
         
-        let is_whitelisted = match state.is_whitelisted(&caller).await {
-            Ok(is_whitelisted) => is_whitelisted,
-            Err(_) => return,
-        };
+        Self::ensure_role(&state, &caller, Role::Curator).await?;
         
-        if !is_whitelisted {
-            return;
-        }
-        
-        if let Ok(Some(_)) = state.get_app_info(&app_id).await {
-            return;
+        if let Some(existing) = state.get_app_info(&app_id).await? {
+            if existing.is_active {
+                return Err(EdgeError::AppAlreadyExists(app_id));
+            }
         }
         
+        let current_time = self.runtime.system_time();
         let app_info = AppInfo {
             app_id: app_id.clone(),
-            name,
+            name: name.clone(),
             description,
-            added_at: self.runtime.system_time(),
+            added_at: current_time,
             is_active: true,
         };
         
-        if let Err(_) = state.add_app_info(app_id.clone(), app_info).await {
-            return;
-        }
+        state.add_app_info(app_id.clone(), app_info).await?;
+        state.update_app_total_bet(app_id.clone(), Amount::ZERO).await?;
         
-        let _ = state.update_app_total_bet(app_id, Amount::ZERO).await;
+        state.save().await?;
+        drop(state);
         
-        let _ = state.save().await;
+        self.emit_event(EdgeEvent::AppAdded(AppAddedEvent { app_id: app_id.clone(), name, timestamp: current_time })).await?;
+        
+        Ok(EdgeResponse::ApplicationAdded { app_id })
     }
 
-    async fn handle_settle(&mut self, caller: AccountOwner) {
+    async fn handle_settle(&mut self, caller: AccountOwner) -> Result<EdgeResponse, EdgeError> {
         let mut state = self.state.lock().await;
         
-        let is_whitelisted = match state.is_whitelisted(&caller).await {
-            Ok(is_whitelisted) => is_whitelisted,
-            Err(_) => return,
-        };
+        Self::ensure_role(&state, &caller, Role::Keeper).await?;
         
-        if !is_whitelisted {
-            return;
+        let current_time = self.runtime.system_time();
+        let last_settle_time = state.get_last_settle_time().await?;
+        
+        let settle_interval_micros = state.get_config().await?.settle_interval_micros;
+        if current_time.micros().saturating_sub(last_settle_time.micros()) < settle_interval_micros {
+            return Err(EdgeError::SettleTooEarly {
+                next_settle_micros: last_settle_time.micros().saturating_add(settle_interval_micros),
+            });
         }
         
-        let current_time = self.runtime.system_time();
-        let last_settle_time = match state.get_last_settle_time().await {
-            Ok(time) => time,
-            Err(_) => return,
-        };
+        let record = Self::execute_settlement_logic(&mut state, current_time).await?;
+        let settled = record.settled_event();
+        state.record_settlement(record).await?;
         
-        let one_minute_micros: u64 = 60_000_000;
-        if current_time.micros() - last_settle_time.micros() < one_minute_micros {
-            return;
+        state.save().await?;
+        drop(state);
+        
+        let (distributed, pool_amount) = (settled.distributed, settled.pool_amount);
+        self.emit_event(EdgeEvent::Settled(settled)).await?;
+        
+        Ok(EdgeResponse::Settled { distributed, pool_amount })
+    }
+
+    async fn execute_settlement_logic(state: &mut EdgeState, current_time: Timestamp) -> Result<SettlementRecord, EdgeError> {
+        state.check_and_perform_resets(current_time).await?;
+        let period_start = state.get_last_settle_time().await?;
+        state.update_last_settle_time(current_time).await?;
+        
+        let pool_amount = state.get_pool_amount().await?;
+        let config = state.get_config().await?;
+        let ranked_apps = config.reward_strategy().ranked_apps();
+        
+        let mut apps = Vec::new();
+        for (app_id, total_bet) in state.get_top_apps(ranked_apps).await? {
+            let app_info = match state.get_app_info(&app_id).await? {
+                Some(info) => info,
+                None => continue,
+            };
+            // Shares follow stake * time held during this period, not the snapshot at settle time.
+            let bettors = state.get_app_bettor_snapshots(&app_id, period_start, current_time).await?;
+            apps.push(AppSnapshot {
+                app_id,
+                total_bet,
+                age_micros: current_time.micros().saturating_sub(app_info.added_at.micros()),
+                bettors,
+            });
         }
         
-        Self::execute_settlement_logic(&mut state, current_time).await;
+        let plan = settlement::plan_settlement(&config, &SettlementSnapshot { apps, pool: pool_amount })?;
         
-        let _ = state.save().await;
+        for payout in &plan.payouts {
+            let current_balance = state.get_user_balance(&payout.owner).await?;
+            state.update_user_balance(&payout.owner, current_balance.saturating_add(payout.amount)).await?;
+            state.update_user_earnings(&payout.owner, payout.amount).await?;
+        }
+        state.update_pool_amount(plan.pool_after).await?;
+        
+        Ok(SettlementRecord {
+            epoch: state.next_settlement_epoch().await?,
+            timestamp: current_time,
+            pool_before: pool_amount,
+            pool_after: plan.pool_after,
+            apps: plan.apps,
+            payouts: plan.payouts,
+        })
     }
 
-    async fn execute_settlement_logic(state: &mut EdgeState, current_time: Timestamp) {
-        let _ = state.check_and_perform_resets(current_time).await;
+    async fn handle_remove_application(&mut self, caller: AccountOwner, app_id: String) -> Result<EdgeResponse, EdgeError> {
+        let mut state = self.state.lock().await;
         
-        let pool_amount = match state.get_pool_amount().await {
-            Ok(amount) => amount,
-            Err(_) => return,
-        };
+        Self::ensure_role(&state, &caller, Role::Curator).await?;
         
-        if pool_amount <= Amount::ZERO {
-            return;
+        match state.get_app_info(&app_id).await? {
+            None => return Err(EdgeError::UnknownApp(app_id)),
+            Some(app_info) if !app_info.is_active => return Err(EdgeError::AppInactive(app_id)),
+            Some(_) => {}
         }
         
-        let app_totals = match state.get_all_app_totals().await {
-            Ok(totals) => totals,
-            Err(_) => return,
-        };
+        // Delisting keeps the `AppInfo` as an inactive record and hands every open
+        // position back without the redeem fee.
+        state.set_app_active(&app_id, false).await?;
+        
+        let bettors = state.get_app_bettors(&app_id).await?;
+        let mut refunded = Amount::ZERO;
+        for (bettor, bet_amount) in &bettors {
+            let current_balance = state.get_user_balance(bettor).await?;
+            state.update_user_balance(bettor, current_balance.saturating_add(*bet_amount)).await?;
+            state.clear_user_bets_for_app(bettor, &app_id).await?;
+            refunded = refunded.saturating_add(*bet_amount);
+        }
         
-        let mut top_apps: Vec<(String, Amount)> = app_totals.into_iter().collect();
-        top_apps.sort_by(|a, b| b.1.cmp(&a.1));
-        let top_apps: Vec<(String, Amount)> = top_apps.into_iter().take(10).collect();
+        state.clear_app_totals(&app_id).await?;
         
-        let reward_weights = [15, 14, 13, 12, 11, 10, 9, 8, 7, 6];
+        state.save().await?;
+        drop(state);
         
-        let total_bets = top_apps.iter().fold(Amount::ZERO, |acc, (_, amount)| acc.saturating_add(*amount));
-        let distribution_amount = if total_bets > Amount::ZERO {
-            total_bets.saturating_mul(1).saturating_div(100)
-        } else {
-            Amount::ZERO
-        };
+        let current_time = self.runtime.system_time();
+        self.emit_event(EdgeEvent::AppRemoved(AppRemovedEvent { app_id: app_id.clone(), refunded, timestamp: current_time })).await?;
         
-        let mut has_eligible_bettors = false;
-        
-        if distribution_amount > Amount::ZERO {
-            for (rank, (app_id, _)) in top_apps.iter().enumerate() {
-                if rank >= reward_weights.len() {
-                    break;
-                }
-                
-                let bettors = match state.get_app_bettors(app_id).await {
-                    Ok(bettors) => bettors,
-                    Err(_) => continue,
-                };
-                
-                let total_bet_for_app = match state.get_app_total_bet(app_id).await {
-                    Ok(total) => total,
-                    Err(_) => continue,
-                };
-                
-                if total_bet_for_app > Amount::ZERO {
-                    let eligible_bettors = bettors;
-                    has_eligible_bettors = !eligible_bettors.is_empty();
-                    
-                    if eligible_bettors.len() > 0 {
-                        let weight = reward_weights[rank];
-                        let base_reward = distribution_amount.saturating_mul(weight as u128).saturating_div(100);
-                        
-                        let supporters_count = match state.get_app_supporters_count(app_id).await {
-                            Ok(count) => count,
-                            Err(_) => 1,
-                        };
-                        
-                        let app_info = match state.get_app_info(app_id).await {
-                            Ok(Some(info)) => info,
-                            _ => continue,
-                        };
-                        
-                        let days_since_added = (current_time.micros() - app_info.added_at.micros()) / (24 * 3600_000_000);
-                        
-                        let supporter_bonus = std::cmp::min((supporters_count as u64) * 1, 10);
-                        
-                        let mut growth_bonus: u64 = 0;
-                        if rank >= 5 {
-                            growth_bonus = ((10 - rank) * 1) as u64;
-                        }
-                        
-                        let mut new_app_bonus: u64 = 0;
-                        if days_since_added < 7 {
-                            new_app_bonus = 5;
-                        }
-                        
-                        let total_bonus_percentage = supporter_bonus + growth_bonus + new_app_bonus;
-                        let total_reward = base_reward.saturating_mul((100 + total_bonus_percentage) as u128).saturating_div(100);
-                        
-                        let eligible_total_bet = eligible_bettors.iter().fold(Amount::ZERO, |acc, (_, amount)| acc.saturating_add(*amount));
-                        
-                        if eligible_total_bet > Amount::ZERO {
-                            for (bettor, bet_amount) in eligible_bettors {
-                                let reward_share = total_reward.saturating_mul(u128::from(bet_amount)).saturating_div(u128::from(eligible_total_bet));
-                                
-                                let current_balance = state.get_user_balance(&bettor).await.unwrap_or(Amount::ZERO);
-                                let _ = state.update_user_balance(&bettor, current_balance.saturating_add(reward_share)).await;
-                                
-                                let _ = state.update_user_earnings(&bettor, reward_share).await;
-                            }
-                        }
-                    }
-                }
-            }
-            
-            if has_eligible_bettors && distribution_amount <= pool_amount {
-                let new_pool = pool_amount.saturating_sub(distribution_amount);
-                let _ = state.update_pool_amount(new_pool).await;
-            }
+        Ok(EdgeResponse::ApplicationRemoved { app_id, refunded, bettors: bettors.len() as u32 })
+    }
+
+    async fn handle_inject_pool(&mut self, caller: AccountOwner, amount: Amount) -> Result<EdgeResponse, EdgeError> {
+        let mut state = self.state.lock().await;
+        
+        Self::ensure_role(&state, &caller, Role::Treasurer).await?;
+        
+        if amount <= Amount::ZERO {
+            return Err(EdgeError::InvalidAmount);
         }
         
-        let _ = state.update_last_settle_time(current_time).await;
+        let current_pool = state.get_pool_amount().await?;
+        let new_pool = current_pool.saturating_add(amount);
+        state.update_pool_amount(new_pool).await?;
+        state.record_mint(amount).await?;
         
-        let _ = state.save().await;
+        state.save().await?;
+        drop(state);
+        
+        let current_time = self.runtime.system_time();
+        self.emit_event(EdgeEvent::PoolInjected(PoolInjectedEvent { amount, pool_amount: new_pool, timestamp: current_time })).await?;
+        
+        Ok(EdgeResponse::PoolInjected { pool_amount: new_pool })
     }
 
-    async fn handle_remove_application(&mut self, caller: AccountOwner, app_id: String) {
+    async fn handle_update_config(&mut self, caller: AccountOwner, config: EdgeConfig) -> Result<EdgeResponse, EdgeError> {
         let mut state = self.state.lock().await;
         
-        let is_whitelisted = match state.is_whitelisted(&caller).await {
-            Ok(is_whitelisted) => is_whitelisted,
-            Err(_) => return,
-        };
+        Self::ensure_owner(&state, &caller).await?;
         
-        if !is_whitelisted {
-            return;
-        }
+        config.validate().map_err(EdgeError::InvalidConfig)?;
         
-        let _ = state.remove_app_info(&app_id).await;
+        let delay_micros = state.get_config().await?.config_update_delay_micros;
+        let effective_at = self.runtime.system_time().saturating_add(TimeDelta::from_micros(delay_micros));
+        state.schedule_config(config, effective_at).await?;
         
-        let _ = state.save().await;
+        state.save().await?;
+        
+        Ok(EdgeResponse::ConfigUpdateScheduled { effective_at })
     }
 
-    async fn handle_inject_pool(&mut self, caller: AccountOwner, amount: Amount) {
+    async fn handle_update_role(&mut self, caller: AccountOwner, address: AccountOwner, role: Role, granted: bool) -> Result<EdgeResponse, EdgeError> {
         let mut state = self.state.lock().await;
         
-        let is_whitelisted = match state.is_whitelisted(&caller).await {
-            Ok(is_whitelisted) => is_whitelisted,
-            Err(_) => return,
-        };
+        Self::ensure_owner(&state, &caller).await?;
         
-        if !is_whitelisted {
-            return;
+        if granted {
+            state.grant_role(&address, role).await?;
+        } else {
+            state.revoke_role(&address, role).await?;
         }
         
-        if amount <= Amount::ZERO {
-            return;
-        }
+        state.save().await?;
         
-        let current_pool = match state.get_pool_amount().await {
-            Ok(pool) => pool,
-            Err(_) => return,
-        };
+        Ok(EdgeResponse::RoleUpdated { address, role, granted })
+    }
+
+    async fn handle_propose_ownership_transfer(&mut self, caller: AccountOwner, new_owner: AccountOwner) -> Result<EdgeResponse, EdgeError> {
+        let mut state = self.state.lock().await;
         
-        let new_pool = current_pool.saturating_add(amount);
+        Self::ensure_owner(&state, &caller).await?;
         
-        let _ = state.update_pool_amount(new_pool).await;
+        state.set_pending_owner(Some(new_owner)).await?;
         
-        let _ = state.save().await;
+        state.save().await?;
+        
+        Ok(EdgeResponse::OwnershipTransferProposed { new_owner })
+    }
+
+    async fn handle_accept_ownership(&mut self, caller: AccountOwner) -> Result<EdgeResponse, EdgeError> {
+        let mut state = self.state.lock().await;
+        
+        if state.get_pending_owner().await? != Some(caller) {
+            return Err(EdgeError::NotPendingOwner);
+        }
+        
+        let previous_owner = state.get_owner().await?;
+        state.accept_ownership(caller).await?;
+        
+        state.save().await?;
+        
+        Ok(EdgeResponse::OwnershipTransferred { previous_owner, new_owner: caller })
     }
 }
 
 impl WithContractAbi for EdgeContract {
     type Abi = linera_edge::EdgeAbi;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_edge::EdgeOperation;
+    use linera_sdk::linera_base_types::{ApplicationId, ChainOwnership, CryptoHash};
+    use linera_sdk::util::BlockingWait;
+
+    const HARDCODED_LEGACY_ADMIN: &str = "0xa0916f957038344afff8c117b0a568562f73f0f2";
+
+    /// A deployment as the first version of the contract left it: owned by its application
+    /// id, with the owner and the hard-coded address in the admin whitelist.
+    fn legacy_deployment(chain_owner: AccountOwner) -> (EdgeContract, AccountOwner) {
+        let application_id = ApplicationId::new(CryptoHash::test_hash("linera-edge")).with_abi::<linera_edge::EdgeAbi>();
+        let application_owner = AccountOwner::from(application_id);
+        let runtime = ContractRuntime::<EdgeContract>::new()
+            .with_application_id(application_id)
+            .with_chain_ownership(ChainOwnership::single(chain_owner))
+            .with_authenticated_signer(chain_owner)
+            .with_system_time(Timestamp::from(0));
+        let mut state = EdgeState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store");
+        state.owner.set(Some(application_owner));
+        state.legacy_whitelist.insert(&application_owner, true).unwrap();
+        state.legacy_whitelist.insert(&HARDCODED_LEGACY_ADMIN.parse().unwrap(), true).unwrap();
+        
+        let contract = EdgeContract { state: Arc::new(Mutex::new(state)), runtime };
+        (contract, application_owner)
+    }
+
+    fn execute(contract: &mut EdgeContract, signer: AccountOwner, operation: EdgeOperation) -> Result<EdgeResponse, EdgeError> {
+        contract.runtime.set_authenticated_signer(signer);
+        contract.dispatch_operation(operation).blocking_wait()
+    }
+
+    #[test]
+    fn chain_owner_takes_over_a_deployment_owned_by_its_application_id() {
+        let chain_owner = AccountOwner::Address20([1; 20]);
+        let keeper = AccountOwner::Address20([2; 20]);
+        let hardcoded: AccountOwner = HARDCODED_LEGACY_ADMIN.parse().unwrap();
+        let (mut contract, application_owner) = legacy_deployment(chain_owner);
+        
+        let grant = EdgeOperation::GrantRole { address: keeper, role: Role::Keeper };
+        assert!(matches!(execute(&mut contract, chain_owner, grant.clone()), Err(EdgeError::NotOwner)));
+        assert!(matches!(execute(&mut contract, hardcoded, EdgeOperation::AcceptOwnership), Err(EdgeError::NotPendingOwner)));
+        assert!(matches!(execute(&mut contract, hardcoded, EdgeOperation::Settle), Err(EdgeError::MissingRole(Role::Keeper))));
+        
+        let accepted = execute(&mut contract, chain_owner, EdgeOperation::AcceptOwnership).unwrap();
+        assert_eq!(accepted, EdgeResponse::OwnershipTransferred { previous_owner: Some(application_owner), new_owner: chain_owner });
+        execute(&mut contract, chain_owner, grant).unwrap();
+        
+        let state = contract.state.try_lock().unwrap();
+        assert!(state.has_role(&keeper, Role::Keeper).blocking_wait().unwrap());
+        assert!(state.get_roles(&hardcoded).blocking_wait().unwrap().is_empty());
+        assert_eq!(state.get_pending_owner().blocking_wait().unwrap(), None);
+    }
+}
@@ -1,26 +1,176 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
+pub mod reward;
+pub mod settlement;
 pub mod state;
+pub mod types;
 
 use serde::{Deserialize, Serialize};
 use linera_sdk::abi::{ContractAbi, ServiceAbi};
-use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, Timestamp};
+use linera_sdk::views::ViewError;
+use thiserror::Error;
+use types::{EdgeConfig, Role};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum EdgeOperation {
-    Bet { caller: AccountOwner, app_id: String, amount: Amount },
-    Redeem { caller: AccountOwner, app_id: String, amount: Amount },
-    Settle { caller: AccountOwner },
-    AddApplication { caller: AccountOwner, app_id: String, name: String, description: String },
-    RemoveApplication { caller: AccountOwner, app_id: String },
-    InjectPool { caller: AccountOwner, amount: Amount },
+    /// Claims the starting grant of `initial_balance` points; required once before betting.
+    Register,
+    Bet { app_id: String, amount: Amount },
+    Redeem { app_id: String, amount: Amount },
+    Settle,
+    AddApplication { app_id: String, name: String, description: String },
+    RemoveApplication { app_id: String },
+    InjectPool { amount: Amount },
+    UpdateConfig { config: EdgeConfig },
+    GrantRole { address: AccountOwner, role: Role },
+    RevokeRole { address: AccountOwner, role: Role },
+    ProposeOwnershipTransfer { new_owner: AccountOwner },
+    AcceptOwnership,
+}
+
+/// Name of the stream carrying `EdgeEvent`s.
+pub const EDGE_EVENTS_STREAM: &str = "edge_events";
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct AppPayout {
+    pub app_id: String,
+    pub rank: u32,
+    pub reward: Amount,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct RegisteredEvent {
+    pub owner: AccountOwner,
+    pub grant: Amount,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct BetPlacedEvent {
+    pub owner: AccountOwner,
+    pub app_id: String,
+    pub amount: Amount,
+    pub position: Amount,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct RedeemedEvent {
+    pub owner: AccountOwner,
+    pub app_id: String,
+    pub amount: Amount,
+    pub fee: Amount,
+    pub penalty: Amount,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct SettledEvent {
+    pub timestamp: Timestamp,
+    pub distributed: Amount,
+    pub pool_amount: Amount,
+    pub payouts: Vec<AppPayout>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct AppAddedEvent {
+    pub app_id: String,
+    pub name: String,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct AppRemovedEvent {
+    pub app_id: String,
+    pub refunded: Amount,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::SimpleObject)]
+pub struct PoolInjectedEvent {
+    pub amount: Amount,
+    pub pool_amount: Amount,
+    pub timestamp: Timestamp,
+}
+
+/// Activity published on `EDGE_EVENTS_STREAM` for indexers and frontends. The same
+/// events are kept in `EdgeState::events` so the service can serve them by index.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, async_graphql::Union)]
+pub enum EdgeEvent {
+    Registered(RegisteredEvent),
+    BetPlaced(BetPlacedEvent),
+    Redeemed(RedeemedEvent),
+    Settled(SettledEvent),
+    AppAdded(AppAddedEvent),
+    AppRemoved(AppRemovedEvent),
+    PoolInjected(PoolInjectedEvent),
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum EdgeResponse {
+    Registered { balance: Amount },
+    BetPlaced { app_id: String, position: Amount, balance: Amount },
+    Redeemed { app_id: String, returned: Amount, fee: Amount, penalty: Amount, balance: Amount },
+    Settled { distributed: Amount, pool_amount: Amount },
+    ApplicationAdded { app_id: String },
+    ApplicationRemoved { app_id: String, refunded: Amount, bettors: u32 },
+    PoolInjected { pool_amount: Amount },
+    ConfigUpdateScheduled { effective_at: Timestamp },
+    RoleUpdated { address: AccountOwner, role: Role, granted: bool },
+    OwnershipTransferProposed { new_owner: AccountOwner },
+    OwnershipTransferred { previous_owner: Option<AccountOwner>, new_owner: AccountOwner },
+}
+
+/// Reasons an operation is rejected. Any error aborts the whole block, so no partial
+/// state is ever committed.
+#[derive(Debug, Error)]
+pub enum EdgeError {
+    #[error("operation must be signed by an account or called by an application")]
+    Unauthenticated,
+    #[error("account has not registered")]
+    NotRegistered,
+    #[error("account is already registered")]
+    AlreadyRegistered,
+    #[error("amount must be greater than zero")]
+    InvalidAmount,
+    #[error("insufficient balance: {available} available, {requested} requested")]
+    InsufficientBalance { available: Amount, requested: Amount },
+    #[error("bet must be at least {minimum}")]
+    BetTooSmall { minimum: Amount },
+    #[error("bet on {app_id} would exceed the per-app cap of {cap}")]
+    BetCapExceeded { app_id: String, cap: Amount },
+    #[error("insufficient bet on {app_id}: {available} placed, {requested} requested")]
+    InsufficientBet { app_id: String, available: Amount, requested: Amount },
+    #[error("bet on {app_id} is locked until {unlock_at}")]
+    BetLocked { app_id: String, unlock_at: Timestamp },
+    #[error("caller does not hold the {0:?} role")]
+    MissingRole(Role),
+    #[error("caller is not the owner")]
+    NotOwner,
+    #[error("caller is not the pending owner")]
+    NotPendingOwner,
+    #[error("unknown application {0}")]
+    UnknownApp(String),
+    #[error("application {0} is not accepting bets")]
+    AppInactive(String),
+    #[error("application {0} already exists")]
+    AppAlreadyExists(String),
+    #[error("settlement is not due before {next_settle_micros}")]
+    SettleTooEarly { next_settle_micros: u64 },
+    #[error("settlement would pay out more than the pool holds")]
+    PoolOverdrawn,
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
+    #[error("storage error: {0}")]
+    Storage(#[from] ViewError),
 }
 
 pub struct EdgeAbi;
 
 impl ContractAbi for EdgeAbi {
     type Operation = EdgeOperation;
-    type Response = ();
+    type Response = EdgeResponse;
 }
 
 impl ServiceAbi for EdgeAbi {
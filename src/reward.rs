@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+use crate::types::{mul_div, EdgeConfig};
+
+/// Built-in reward curves, selected by `EdgeConfig::reward_strategy`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default, async_graphql::Enum)]
+pub enum RewardStrategyKind {
+    /// `reward_weights` per rank plus the supporter, growth and new-app bonuses.
+    #[default]
+    FixedWeights,
+    /// Rank `r` of `n` gets a share proportional to `n - r`.
+    LinearDecay,
+    /// Each rank gets `decay_percent` of the share of the rank above it.
+    ExponentialDecay,
+    /// The top app gets `winner_percent`, the others split the rest evenly.
+    WinnerTakeMost,
+    /// Shares proportional to `(sum of sqrt(stake))^2` over each app's supporters.
+    Quadratic,
+}
+
+/// What a strategy sees of one ranked app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedApp {
+    /// Zero-based position in the ranking by total bet.
+    pub rank: usize,
+    /// Current stake of each supporter, in attos.
+    pub stakes: Vec<u128>,
+    pub age_micros: u64,
+}
+
+/// Reward planned for one app, in attos, before it is capped at the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppReward {
+    pub base_reward: u128,
+    pub bonus_percent: u64,
+    pub reward: u128,
+}
+
+impl AppReward {
+    fn without_bonus(base_reward: u128) -> Self {
+        Self { base_reward, bonus_percent: 0, reward: base_reward }
+    }
+}
+
+/// Splits a settlement's distribution between the ranked apps.
+pub trait RewardStrategy {
+    /// How many of the top apps take part in a settlement.
+    fn ranked_apps(&self) -> usize;
+
+    /// Rewards for `apps`, in the same order. `distribution` is the amount in attos
+    /// set aside for this settlement.
+    fn allocate(&self, distribution: u128, apps: &[RankedApp]) -> Vec<AppReward>;
+}
+
+/// Splits `distribution` between `apps` proportionally to `score(app)`.
+fn proportional(distribution: u128, apps: &[RankedApp], score: impl Fn(&RankedApp) -> u128) -> Vec<AppReward> {
+    let scores: Vec<u128> = apps.iter().map(score).collect();
+    let total = scores.iter().fold(0u128, |acc, score| acc.saturating_add(*score));
+    scores
+        .into_iter()
+        .map(|score| AppReward::without_bonus(mul_div(distribution, score, total)))
+        .collect()
+}
+
+/// Integer square root, rounded down.
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = 1u128 << ((128 - value.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+pub struct FixedWeights<'a> {
+    pub config: &'a EdgeConfig,
+}
+
+impl RewardStrategy for FixedWeights<'_> {
+    fn ranked_apps(&self) -> usize {
+        self.config.reward_weights.len()
+    }
+
+    fn allocate(&self, distribution: u128, apps: &[RankedApp]) -> Vec<AppReward> {
+        apps.iter()
+            .map(|app| {
+                let weight = self.config.reward_weights.get(app.rank).copied().unwrap_or(0);
+                let base_reward = mul_div(distribution, weight as u128, 100);
+                let bonus_percent = self.config.bonus_percent(app.rank, app.stakes.len() as u32, app.age_micros);
+                let reward = mul_div(base_reward, 100 + bonus_percent as u128, 100);
+                AppReward { base_reward, bonus_percent, reward }
+            })
+            .collect()
+    }
+}
+
+pub struct LinearDecay {
+    pub ranked_apps: usize,
+}
+
+impl RewardStrategy for LinearDecay {
+    fn ranked_apps(&self) -> usize {
+        self.ranked_apps
+    }
+
+    fn allocate(&self, distribution: u128, apps: &[RankedApp]) -> Vec<AppReward> {
+        proportional(distribution, apps, |app| self.ranked_apps.saturating_sub(app.rank) as u128)
+    }
+}
+
+pub struct ExponentialDecay {
+    pub ranked_apps: usize,
+    pub decay_percent: u64,
+}
+
+impl ExponentialDecay {
+    const TOP_WEIGHT: u128 = 1_000_000_000_000;
+
+    fn weight(&self, rank: usize) -> u128 {
+        (0..rank).fold(Self::TOP_WEIGHT, |weight, _| mul_div(weight, self.decay_percent as u128, 100))
+    }
+}
+
+impl RewardStrategy for ExponentialDecay {
+    fn ranked_apps(&self) -> usize {
+        self.ranked_apps
+    }
+
+    fn allocate(&self, distribution: u128, apps: &[RankedApp]) -> Vec<AppReward> {
+        proportional(distribution, apps, |app| self.weight(app.rank))
+    }
+}
+
+pub struct WinnerTakeMost {
+    pub ranked_apps: usize,
+    pub winner_percent: u64,
+}
+
+impl RewardStrategy for WinnerTakeMost {
+    fn ranked_apps(&self) -> usize {
+        self.ranked_apps
+    }
+
+    fn allocate(&self, distribution: u128, apps: &[RankedApp]) -> Vec<AppReward> {
+        let winner_share = mul_div(distribution, self.winner_percent as u128, 100);
+        let runners_up = apps.iter().filter(|app| app.rank > 0).count() as u128;
+        let runner_up_share = if runners_up == 0 {
+            0
+        } else {
+            mul_div(distribution, 100u128.saturating_sub(self.winner_percent as u128), 100) / runners_up
+        };
+        apps.iter()
+            .map(|app| AppReward::without_bonus(if app.rank == 0 { winner_share } else { runner_up_share }))
+            .collect()
+    }
+}
+
+pub struct Quadratic {
+    pub ranked_apps: usize,
+}
+
+impl Quadratic {
+    fn score(app: &RankedApp) -> u128 {
+        let root_sum = app.stakes.iter().fold(0u128, |acc, stake| acc.saturating_add(isqrt(*stake)));
+        root_sum.saturating_mul(root_sum)
+    }
+}
+
+impl RewardStrategy for Quadratic {
+    fn ranked_apps(&self) -> usize {
+        self.ranked_apps
+    }
+
+    fn allocate(&self, distribution: u128, apps: &[RankedApp]) -> Vec<AppReward> {
+        proportional(distribution, apps, Self::score)
+    }
+}
+
+impl EdgeConfig {
+    /// The strategy selected by this config.
+    pub fn reward_strategy(&self) -> Box<dyn RewardStrategy + '_> {
+        let ranked_apps = self.ranked_apps as usize;
+        match self.reward_strategy {
+            RewardStrategyKind::FixedWeights => Box::new(FixedWeights { config: self }),
+            RewardStrategyKind::LinearDecay => Box::new(LinearDecay { ranked_apps }),
+            RewardStrategyKind::ExponentialDecay => Box::new(ExponentialDecay { ranked_apps, decay_percent: self.decay_percent }),
+            RewardStrategyKind::WinnerTakeMost => Box::new(WinnerTakeMost { ranked_apps, winner_percent: self.winner_percent }),
+            RewardStrategyKind::Quadratic => Box::new(Quadratic { ranked_apps }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(rank: usize, stakes: &[u128]) -> RankedApp {
+        RankedApp { rank, stakes: stakes.to_vec(), age_micros: u64::MAX }
+    }
+
+    fn rewards(strategy: &dyn RewardStrategy, distribution: u128, apps: &[RankedApp]) -> Vec<u128> {
+        strategy.allocate(distribution, apps).iter().map(|app_reward| app_reward.reward).collect()
+    }
+
+    #[test]
+    fn isqrt_rounds_down() {
+        let cases = [(0, 0), (1, 1), (2, 1), (3, 1), (4, 2), (15, 3), (16, 4), (17, 4), (1_000_000, 1_000)];
+        for (value, root) in cases {
+            assert_eq!(isqrt(value), root, "isqrt({value})");
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn fixed_weights_apply_weights_and_bonuses() {
+        let config = EdgeConfig {
+            reward_weights: vec![60, 40],
+            supporter_bonus_percent: 10,
+            max_supporter_bonus_percent: 100,
+            growth_bonus_percent_per_rank: 0,
+            new_app_bonus_percent: 0,
+            ..EdgeConfig::default()
+        };
+        let allocation = FixedWeights { config: &config }.allocate(1_000, &[app(0, &[1]), app(1, &[1, 1])]);
+
+        assert_eq!(allocation[0], AppReward { base_reward: 600, bonus_percent: 10, reward: 660 });
+        assert_eq!(allocation[1], AppReward { base_reward: 400, bonus_percent: 20, reward: 480 });
+    }
+
+    #[test]
+    fn linear_decay_is_proportional_to_remaining_ranks() {
+        let strategy = LinearDecay { ranked_apps: 3 };
+        let apps = [app(0, &[1]), app(1, &[1]), app(2, &[1])];
+        assert_eq!(rewards(&strategy, 600, &apps), vec![300, 200, 100]);
+    }
+
+    #[test]
+    fn exponential_decay_scales_each_rank() {
+        let strategy = ExponentialDecay { ranked_apps: 3, decay_percent: 50 };
+        let apps = [app(0, &[1]), app(1, &[1]), app(2, &[1])];
+        assert_eq!(rewards(&strategy, 700, &apps), vec![400, 200, 100]);
+    }
+
+    #[test]
+    fn winner_take_most_splits_the_rest_evenly() {
+        let strategy = WinnerTakeMost { ranked_apps: 3, winner_percent: 50 };
+        let apps = [app(0, &[1]), app(1, &[1]), app(2, &[1])];
+        assert_eq!(rewards(&strategy, 1_000, &apps), vec![500, 250, 250]);
+        assert_eq!(rewards(&strategy, 1_000, &apps[..1]), vec![500]);
+    }
+
+    #[test]
+    fn quadratic_favours_broad_support() {
+        let strategy = Quadratic { ranked_apps: 2 };
+        let apps = [app(0, &[100]), app(1, &[25, 25, 25, 25])];
+        assert_eq!(rewards(&strategy, 500, &apps), vec![100, 400]);
+    }
+
+    #[test]
+    fn proportional_strategies_pay_nothing_without_scores() {
+        let strategy = Quadratic { ranked_apps: 1 };
+        assert_eq!(rewards(&strategy, 500, &[app(0, &[])]), vec![0]);
+    }
+}
@@ -1,6 +1,10 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
-use linera_edge::state::{EdgeState, AppInfo};
+use linera_edge::state::{ArchivedLeaderboard, EdgeState, AppInfo, LoggedEvent, SettlementRecord, SupplyAudit};
+use linera_edge::types::{EdgeConfig, LeaderboardPeriod, PendingConfig, Role};
+use linera_edge::{BetPlacedEvent, EdgeEvent, SettledEvent};
+use linera_sdk::views::ViewError;
+use futures::StreamExt;
 use async_graphql::{Object, Request, Response, Schema, SimpleObject, Subscription};
 use linera_sdk::{Service, ServiceRuntime};
 use linera_sdk::abi::WithServiceAbi;
@@ -43,14 +47,55 @@ impl Service for EdgeService {
             MutationRoot {
                 runtime: Arc::clone(&self.runtime),
             },
-            SubscriptionRoot {},
+            SubscriptionRoot {
+                state: Arc::clone(&self.state),
+            },
         )
         .finish();
-        schema.execute(request).await
+        // A service query sees a single state snapshot, so every subscription stream
+        // yields once and ends; queries and mutations produce exactly one response.
+        let responses = schema.execute_stream(request).collect::<Vec<_>>().await;
+        merge_responses(responses)
     }
 }
 
+fn merge_responses(responses: Vec<Response>) -> Response {
+    let mut responses = responses.into_iter();
+    let mut merged = match responses.next() {
+        Some(response) => response,
+        None => return Response::default(),
+    };
+    for response in responses {
+        if let (async_graphql::Value::Object(merged_data), async_graphql::Value::Object(data)) = (&mut merged.data, response.data) {
+            merged_data.extend(data);
+        }
+        merged.errors.extend(response.errors);
+    }
+    merged
+}
 
+async fn top_app_rankings(state: &EdgeState, limit: usize) -> Result<Vec<AppRanking>, ViewError> {
+    let mut rankings = Vec::new();
+    for (rank, (app_id, total_bet)) in state.get_top_apps(limit).await?.into_iter().enumerate() {
+        let name = match state.get_app_info(&app_id).await? {
+            Some(app_info) => app_info.name,
+            None => continue,
+        };
+        let pool_contribution = state.get_app_pool_contribution(&app_id).await?;
+        let supporters = state.get_app_supporters_count(&app_id).await?;
+        
+        rankings.push(AppRanking {
+            app_id,
+            name,
+            total_bet,
+            pool_contribution,
+            rank: (rank + 1) as u32,
+            supporters,
+        });
+    }
+    
+    Ok(rankings)
+}
 
 pub struct QueryRoot {
     state: Arc<Mutex<EdgeState>>,
@@ -62,6 +107,7 @@ pub struct UserBet {
     pub app_id: String,
     pub amount: Amount,
     pub timestamp: Timestamp,
+    pub unlock_at: Timestamp,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
@@ -81,6 +127,14 @@ pub struct UserRanking {
     pub rank: u32,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
+pub struct UserEpochPayout {
+    pub epoch: u64,
+    pub timestamp: Timestamp,
+    pub app_id: String,
+    pub amount: Amount,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
 pub struct UserEarningsData {
     pub daily: Amount,
@@ -88,18 +142,34 @@ pub struct UserEarningsData {
     pub monthly: Amount,
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
+pub struct AccountData {
+    pub owner: AccountOwner,
+    pub balance: Amount,
+    pub is_registered: bool,
+}
+
 #[Object]
 impl QueryRoot {
+    /// On-chain balance; zero for accounts that have not registered.
     async fn get_balance(&self, owner: Option<AccountOwner>) -> async_graphql::Result<Amount> {
-        let mut state = self.state.lock().await;
+        let state = self.state.lock().await;
         let owner = match owner {
             Some(owner) => owner,
             None => return Err(async_graphql::Error::new("Owner must be specified")),
         };
-        let balance = state.get_user_balance_with_initialization(&owner).await?;
+        let balance = state.get_user_balance(&owner).await?;
         Ok(balance)
     }
 
+    /// Balance together with whether the account has claimed its starting grant.
+    async fn get_account(&self, owner: AccountOwner) -> async_graphql::Result<AccountData> {
+        let state = self.state.lock().await;
+        let balance = state.get_user_balance(&owner).await?;
+        let is_registered = state.is_registered(&owner).await?;
+        Ok(AccountData { owner, balance, is_registered })
+    }
+
     async fn get_user_bets(&self, owner: Option<AccountOwner>) -> async_graphql::Result<Vec<UserBet>> {
         let state = self.state.lock().await;
         let owner = match owner {
@@ -114,6 +184,7 @@ impl QueryRoot {
                 app_id: bet.app_id,
                 amount: bet.amount,
                 timestamp: bet.timestamp,
+                unlock_at: bet.unlock_at,
             });
         }
         
@@ -128,42 +199,7 @@ impl QueryRoot {
 
     async fn get_top_apps(&self, limit: Option<u32>) -> async_graphql::Result<Vec<AppRanking>> {
         let state = self.state.lock().await;
-        let limit = limit.unwrap_or(10);
-        
-        let mut all_apps = Vec::new();
-        state.app_info
-            .for_each_index_value(|app_id, app_info| {
-                all_apps.push((app_id.clone(), app_info.clone()));
-                Ok(())
-            })
-            .await?;
-        
-        let app_totals = state.get_all_app_totals().await?;
-        let totals_map: std::collections::HashMap<String, Amount> = app_totals.into_iter().collect();
-        
-        let mut app_rankings = Vec::new();
-        for (app_id, app_info) in all_apps {
-            let total_bet = totals_map.get(&app_id).copied().unwrap_or(Amount::ZERO);
-            app_rankings.push((app_id, app_info, total_bet));
-        }
-        
-        app_rankings.sort_by(|a, b| b.2.cmp(&a.2));
-        
-        let mut rankings = Vec::new();
-        for (rank, (app_id, app_info, total_bet)) in app_rankings.into_iter().take(limit as usize).enumerate() {
-            let pool_contribution = state.get_app_pool_contribution(&app_id).await.unwrap_or(Amount::ZERO);
-            let supporters = state.get_app_supporters_count(&app_id).await.unwrap_or(0);
-            
-            rankings.push(AppRanking {
-                app_id,
-                name: app_info.name.clone(),
-                total_bet,
-                pool_contribution,
-                rank: (rank + 1) as u32,
-                supporters,
-            });
-        }
-        
+        let rankings = top_app_rankings(&state, limit.unwrap_or(10) as usize).await?;
         Ok(rankings)
     }
 
@@ -173,6 +209,54 @@ impl QueryRoot {
         Ok(pool_amount)
     }
 
+    async fn get_settlement(&self, epoch: u64) -> async_graphql::Result<Option<SettlementRecord>> {
+        let state = self.state.lock().await;
+        let settlement = state.get_settlement(epoch).await?;
+        Ok(settlement)
+    }
+
+    async fn get_settlement_count(&self) -> async_graphql::Result<u64> {
+        let state = self.state.lock().await;
+        let count = state.next_settlement_epoch().await?;
+        Ok(count)
+    }
+
+    /// The owner's payouts in epochs `fromEpoch..=toEpoch`, reading at most `limit` epochs
+    /// (default and maximum 100) from `fromEpoch`; page by moving `fromEpoch` on by `limit`.
+    async fn get_user_payouts(
+        &self,
+        owner: AccountOwner,
+        from_epoch: Option<u64>,
+        to_epoch: Option<u64>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<Vec<UserEpochPayout>> {
+        let state = self.state.lock().await;
+        let limit = std::cmp::min(limit.map_or(Self::MAX_PAYOUT_EPOCHS, |limit| limit as usize), Self::MAX_PAYOUT_EPOCHS);
+        let settlements = state.get_settlements(from_epoch.unwrap_or(0), to_epoch.unwrap_or(u64::MAX), limit).await?;
+        
+        let mut payouts = Vec::new();
+        for settlement in settlements {
+            for payout in settlement.payouts {
+                if payout.owner == owner {
+                    payouts.push(UserEpochPayout {
+                        epoch: settlement.epoch,
+                        timestamp: settlement.timestamp,
+                        app_id: payout.app_id,
+                        amount: payout.amount,
+                    });
+                }
+            }
+        }
+        
+        Ok(payouts)
+    }
+
+    async fn get_supply_audit(&self) -> async_graphql::Result<SupplyAudit> {
+        let state = self.state.lock().await;
+        let audit = state.audit_supply().await?;
+        Ok(audit)
+    }
+
     async fn get_active_users_count(&self) -> async_graphql::Result<u64> {
         let state = self.state.lock().await;
         let count = state.get_active_users_count().await?;
@@ -188,18 +272,67 @@ impl QueryRoot {
         }
     }
 
+    async fn get_event_count(&self) -> async_graphql::Result<u64> {
+        let state = self.state.lock().await;
+        let count = state.get_event_count().await?;
+        Ok(count)
+    }
+
+    async fn get_events(&self, from_index: Option<u64>, limit: Option<u32>) -> async_graphql::Result<Vec<EdgeEvent>> {
+        let state = self.state.lock().await;
+        let events = state.get_events(from_index.unwrap_or(0), limit.unwrap_or(100) as usize).await?;
+        Ok(events)
+    }
+
+    async fn get_pending_owner(&self) -> async_graphql::Result<Option<AccountOwner>> {
+        let state = self.state.lock().await;
+        let pending_owner = state.get_pending_owner().await?;
+        Ok(pending_owner)
+    }
+
     async fn get_last_settle_time(&self) -> async_graphql::Result<Timestamp> {
         let state = self.state.lock().await;
         let last_settle_time = state.get_last_settle_time().await?;
         Ok(last_settle_time)
     }
 
+    async fn get_config(&self) -> async_graphql::Result<EdgeConfig> {
+        let state = self.state.lock().await;
+        let config = state.get_active_config(self.runtime.system_time()).await?;
+        Ok(config)
+    }
+
+    async fn get_pending_config(&self) -> async_graphql::Result<Option<PendingConfig>> {
+        let state = self.state.lock().await;
+        let current_time = self.runtime.system_time();
+        let pending_config = state.get_pending_config().await?;
+        Ok(pending_config.filter(|pending| pending.effective_at > current_time))
+    }
+
     async fn is_whitelisted(&self, _ctx: &async_graphql::Context<'_>, address: AccountOwner) -> async_graphql::Result<bool> {
         let state = self.state.lock().await;
         let is_whitelisted = state.is_whitelisted(&address).await?;
         Ok(is_whitelisted)
     }
 
+    async fn get_roles(&self, address: AccountOwner) -> async_graphql::Result<Vec<Role>> {
+        let state = self.state.lock().await;
+        let roles = state.get_roles(&address).await?;
+        Ok(roles)
+    }
+
+    async fn has_role(&self, address: AccountOwner, role: Role) -> async_graphql::Result<bool> {
+        let state = self.state.lock().await;
+        let has_role = state.has_role(&address, role).await?;
+        Ok(has_role)
+    }
+
+    async fn get_role_members(&self, role: Role) -> async_graphql::Result<Vec<AccountOwner>> {
+        let state = self.state.lock().await;
+        let members = state.get_role_members(role).await?;
+        Ok(members)
+    }
+
     async fn get_app_info(&self, app_id: String) -> async_graphql::Result<Option<AppInfo>> {
         let state = self.state.lock().await;
         let app_info = state.get_app_info(&app_id).await?;
@@ -244,42 +377,7 @@ impl QueryRoot {
 
     async fn get_all_apps_for_betting(&self) -> async_graphql::Result<Vec<AppRanking>> {
         let state = self.state.lock().await;
-        
-        let mut all_apps = Vec::new();
-        state.app_info
-            .for_each_index_value(|app_id, app_info| {
-                all_apps.push((app_id.clone(), app_info.clone().into_owned()));
-                Ok(())
-            })
-            .await?;
-        
-        let app_totals = state.get_all_app_totals().await?;
-        
-        let totals_map: std::collections::HashMap<String, Amount> = app_totals.into_iter().collect();
-        
-        let mut rankings = Vec::new();
-        for (app_id, app_info) in all_apps {
-            let total_bet = totals_map.get(&app_id).copied().unwrap_or(Amount::ZERO);
-            let pool_contribution = state.get_app_pool_contribution(&app_id).await.unwrap_or(Amount::ZERO);
-            let supporters = state.get_app_supporters_count(&app_id).await.unwrap_or(0);
-            
-            rankings.push(AppRanking {
-                app_id,
-                name: app_info.name.clone(),
-                total_bet,
-                pool_contribution,
-                rank: 0,
-                supporters,
-            });
-        }
-        
-        rankings.sort_by(|a, b| b.total_bet.cmp(&a.total_bet));
-        
-        
-        for (rank, app) in rankings.iter_mut().enumerate() {
-            app.rank = (rank + 1) as u32;
-        }
-        
+        let rankings = top_app_rankings(&state, usize::MAX).await?;
         Ok(rankings)
     }
 
@@ -287,7 +385,7 @@ impl QueryRoot {
         let state = self.state.lock().await;
         let limit = limit.unwrap_or(10) as usize;
         
-        let leaderboard = state.get_daily_leaderboard(limit).await?;
+        let leaderboard = state.get_daily_leaderboard(limit, self.runtime.system_time()).await?;
         
         let mut rankings = Vec::new();
         for (rank, (user, earnings)) in leaderboard.into_iter().enumerate() {
@@ -305,7 +403,7 @@ impl QueryRoot {
         let state = self.state.lock().await;
         let limit = limit.unwrap_or(10) as usize;
         
-        let leaderboard = state.get_weekly_leaderboard(limit).await?;
+        let leaderboard = state.get_weekly_leaderboard(limit, self.runtime.system_time()).await?;
         
         let mut rankings = Vec::new();
         for (rank, (user, earnings)) in leaderboard.into_iter().enumerate() {
@@ -323,7 +421,7 @@ impl QueryRoot {
         let state = self.state.lock().await;
         let limit = limit.unwrap_or(10) as usize;
         
-        let leaderboard = state.get_monthly_leaderboard(limit).await?;
+        let leaderboard = state.get_monthly_leaderboard(limit, self.runtime.system_time()).await?;
         
         let mut rankings = Vec::new();
         for (rank, (user, earnings)) in leaderboard.into_iter().enumerate() {
@@ -337,6 +435,39 @@ impl QueryRoot {
         Ok(rankings)
     }
 
+    /// Rank among all earners of the current period; null without earnings in it.
+    async fn get_user_rank(&self, owner: AccountOwner, period: LeaderboardPeriod) -> async_graphql::Result<Option<u32>> {
+        let state = self.state.lock().await;
+        let rank = state.get_user_rank(&owner, period, self.runtime.system_time()).await?;
+        Ok(rank)
+    }
+
+    /// Index of the period containing `at` (default: now), for the archive queries.
+    async fn get_period_index(&self, period: LeaderboardPeriod, at: Option<Timestamp>) -> u64 {
+        period.index_at(at.unwrap_or_else(|| self.runtime.system_time()))
+    }
+
+    /// Final leaderboard of a closed period; defaults to the one before the current period.
+    async fn get_archived_leaderboard(&self, period: LeaderboardPeriod, index: Option<u64>) -> async_graphql::Result<Option<ArchivedLeaderboard>> {
+        let state = self.state.lock().await;
+        let current_time = self.runtime.system_time();
+        let index = match index {
+            Some(index) => index,
+            None => match period.index_at(current_time).checked_sub(1) {
+                Some(index) => index,
+                None => return Ok(None),
+            },
+        };
+        let leaderboard = state.get_archived_leaderboard(period, index, current_time).await?;
+        Ok(leaderboard)
+    }
+
+    async fn get_user_period_earnings(&self, owner: AccountOwner, period: LeaderboardPeriod, index: u64) -> async_graphql::Result<Amount> {
+        let state = self.state.lock().await;
+        let earnings = state.get_user_period_earnings(&owner, period, index).await?;
+        Ok(earnings)
+    }
+
     async fn get_user_earnings(&self, user: Option<AccountOwner>) -> async_graphql::Result<UserEarningsData> {
         let state = self.state.lock().await;
         let user = match user {
@@ -344,9 +475,10 @@ impl QueryRoot {
             None => return Err(async_graphql::Error::new("User must be specified")),
         };
         
-        let daily = state.get_user_daily_earnings(&user).await?;
-        let weekly = state.get_user_weekly_earnings(&user).await?;
-        let monthly = state.get_user_monthly_earnings(&user).await?;
+        let current_time = self.runtime.system_time();
+        let daily = state.get_user_daily_earnings(&user, current_time).await?;
+        let weekly = state.get_user_weekly_earnings(&user, current_time).await?;
+        let monthly = state.get_user_monthly_earnings(&user, current_time).await?;
         
         Ok(UserEarningsData {
             daily,
@@ -356,11 +488,44 @@ impl QueryRoot {
     }
 }
 
+impl QueryRoot {
+    /// Most settlement epochs a single `getUserPayouts` query reads.
+    const MAX_PAYOUT_EPOCHS: usize = 100;
+}
+
 pub struct MutationRoot {
     runtime: Arc<ServiceRuntime<EdgeService>>,
 }
 
 pub struct SubscriptionRoot {
+    state: Arc<Mutex<EdgeState>>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
+pub struct BetPlacedBatch {
+    pub events: Vec<BetPlacedEvent>,
+    pub next_index: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
+pub struct SettlementBatch {
+    pub settlements: Vec<SettledEvent>,
+    pub next_index: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
+pub struct RankingsUpdate {
+    pub changed: bool,
+    pub rankings: Vec<AppRanking>,
+    pub next_index: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, SimpleObject)]
+pub struct BalanceUpdate {
+    pub owner: AccountOwner,
+    pub changed: bool,
+    pub balance: Amount,
+    pub next_index: u64,
 }
 
 #[Object]
@@ -369,17 +534,23 @@ impl MutationRoot {
         Ok(true)
     }
 
+    async fn register(&self, _ctx: &async_graphql::Context<'_>) -> async_graphql::Result<bool> {
+        let operation = linera_edge::EdgeOperation::Register;
+        
+        self.runtime.schedule_operation(&operation);
+        
+        Ok(true)
+    }
+
     async fn add_application(
         &self,
         _ctx: &async_graphql::Context<'_>,
-        caller: AccountOwner,
         app_id: String,
         name: String,
         description: String,
     ) -> async_graphql::Result<String> {
         
         let operation = linera_edge::EdgeOperation::AddApplication {
-            caller,
             app_id,
             name,
             description,
@@ -393,12 +564,10 @@ impl MutationRoot {
     async fn remove_application(
         &self,
         _ctx: &async_graphql::Context<'_>,
-        caller: AccountOwner,
         app_id: String,
     ) -> async_graphql::Result<String> {
         
         let operation = linera_edge::EdgeOperation::RemoveApplication {
-            caller,
             app_id,
         };
         
@@ -410,13 +579,11 @@ impl MutationRoot {
     async fn place_bet(
         &self,
         _ctx: &async_graphql::Context<'_>,
-        caller: AccountOwner,
         app_id: String,
         amount: Amount,
     ) -> async_graphql::Result<bool> {
         
         let operation = linera_edge::EdgeOperation::Bet {
-            caller,
             app_id,
             amount,
         };
@@ -429,13 +596,11 @@ impl MutationRoot {
     async fn redeem_bet(
         &self,
         _ctx: &async_graphql::Context<'_>,
-        caller: AccountOwner,
         app_id: String,
         amount: Amount,
     ) -> async_graphql::Result<Amount> {
         
         let operation = linera_edge::EdgeOperation::Redeem {
-            caller,
             app_id,
             amount,
         };
@@ -448,12 +613,9 @@ impl MutationRoot {
     async fn settle(
         &self,
         _ctx: &async_graphql::Context<'_>,
-        caller: AccountOwner,
     ) -> async_graphql::Result<bool> {
         
-        let operation = linera_edge::EdgeOperation::Settle {
-            caller,
-        };
+        let operation = linera_edge::EdgeOperation::Settle;
         
         self.runtime.schedule_operation(&operation);
         
@@ -463,12 +625,10 @@ impl MutationRoot {
     async fn inject_pool(
         &self,
         _ctx: &async_graphql::Context<'_>,
-        caller: AccountOwner,
         amount: Amount,
     ) -> async_graphql::Result<bool> {
         
         let operation = linera_edge::EdgeOperation::InjectPool {
-            caller,
             amount,
         };
         
@@ -476,13 +636,187 @@ impl MutationRoot {
         
         Ok(true)
     }
+
+    async fn update_config(
+        &self,
+        _ctx: &async_graphql::Context<'_>,
+        config: EdgeConfig,
+    ) -> async_graphql::Result<bool> {
+        
+        let operation = linera_edge::EdgeOperation::UpdateConfig {
+            config,
+        };
+        
+        self.runtime.schedule_operation(&operation);
+        
+        Ok(true)
+    }
+
+    async fn grant_role(
+        &self,
+        _ctx: &async_graphql::Context<'_>,
+        address: AccountOwner,
+        role: Role,
+    ) -> async_graphql::Result<bool> {
+        
+        let operation = linera_edge::EdgeOperation::GrantRole {
+            address,
+            role,
+        };
+        
+        self.runtime.schedule_operation(&operation);
+        
+        Ok(true)
+    }
+
+    async fn revoke_role(
+        &self,
+        _ctx: &async_graphql::Context<'_>,
+        address: AccountOwner,
+        role: Role,
+    ) -> async_graphql::Result<bool> {
+        
+        let operation = linera_edge::EdgeOperation::RevokeRole {
+            address,
+            role,
+        };
+        
+        self.runtime.schedule_operation(&operation);
+        
+        Ok(true)
+    }
+
+    async fn propose_ownership_transfer(
+        &self,
+        _ctx: &async_graphql::Context<'_>,
+        new_owner: AccountOwner,
+    ) -> async_graphql::Result<bool> {
+        
+        let operation = linera_edge::EdgeOperation::ProposeOwnershipTransfer {
+            new_owner,
+        };
+        
+        self.runtime.schedule_operation(&operation);
+        
+        Ok(true)
+    }
+
+    async fn accept_ownership(
+        &self,
+        _ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<bool> {
+        
+        let operation = linera_edge::EdgeOperation::AcceptOwnership;
+        
+        self.runtime.schedule_operation(&operation);
+        
+        Ok(true)
+    }
 }
 
+/// Subscriptions are answered from the event log: clients pass the `nextIndex` of their
+/// previous answer as `fromIndex` and re-subscribe whenever the chain's `notifications`
+/// report a new block.
 #[Subscription]
 impl SubscriptionRoot {
-    async fn dummy_subscription(&self) -> impl futures::Stream<Item = async_graphql::Result<String>> {
-        use async_graphql::futures_util::stream;
-        
-        stream::once(async { Ok("Subscription is working".to_string()) })
+    async fn bet_placed(
+        &self,
+        app_id: Option<String>,
+        from_index: Option<u64>,
+    ) -> impl futures::Stream<Item = async_graphql::Result<BetPlacedBatch>> {
+        let batch = self.bet_placed_batch(app_id, from_index).await;
+        futures::stream::once(async move { batch })
+    }
+
+    async fn settlement_completed(
+        &self,
+        from_index: Option<u64>,
+    ) -> impl futures::Stream<Item = async_graphql::Result<SettlementBatch>> {
+        let batch = self.settlement_batch(from_index).await;
+        futures::stream::once(async move { batch })
+    }
+
+    async fn rankings_changed(
+        &self,
+        limit: Option<u32>,
+        from_index: Option<u64>,
+    ) -> impl futures::Stream<Item = async_graphql::Result<RankingsUpdate>> {
+        let update = self.rankings_update(limit.unwrap_or(10), from_index).await;
+        futures::stream::once(async move { update })
+    }
+
+    async fn balance_changed(
+        &self,
+        owner: AccountOwner,
+        from_index: Option<u64>,
+    ) -> impl futures::Stream<Item = async_graphql::Result<BalanceUpdate>> {
+        let update = self.balance_update(owner, from_index).await;
+        futures::stream::once(async move { update })
+    }
+}
+
+impl SubscriptionRoot {
+    /// Most log entries a single subscription batch reads; clients page with `next_index`.
+    const MAX_BATCH_EVENTS: usize = 100;
+
+    async fn events_since(state: &EdgeState, from_index: Option<u64>) -> Result<(Vec<LoggedEvent>, u64), ViewError> {
+        let count = state.get_event_count().await?;
+        let from_index = std::cmp::min(from_index.unwrap_or(count), count);
+        let events = state.get_logged_events(from_index, Self::MAX_BATCH_EVENTS).await?;
+        let next_index = from_index + events.len() as u64;
+        Ok((events, next_index))
     }
-}
\ No newline at end of file
+
+    async fn bet_placed_batch(&self, app_id: Option<String>, from_index: Option<u64>) -> async_graphql::Result<BetPlacedBatch> {
+        let state = self.state.lock().await;
+        let (events, next_index) = Self::events_since(&state, from_index).await?;
+        let events = events
+            .into_iter()
+            .filter_map(|event| match event {
+                LoggedEvent::BetPlaced(bet) if app_id.as_ref().map_or(true, |app_id| *app_id == bet.app_id) => Some(bet),
+                _ => None,
+            })
+            .collect();
+        Ok(BetPlacedBatch { events, next_index })
+    }
+
+    async fn settlement_batch(&self, from_index: Option<u64>) -> async_graphql::Result<SettlementBatch> {
+        let state = self.state.lock().await;
+        let (events, next_index) = Self::events_since(&state, from_index).await?;
+        let mut settlements = Vec::new();
+        for event in events {
+            if let LoggedEvent::Settled { epoch } = event {
+                if let Some(record) = state.get_settlement(epoch).await? {
+                    settlements.push(record.settled_event());
+                }
+            }
+        }
+        Ok(SettlementBatch { settlements, next_index })
+    }
+
+    async fn rankings_update(&self, limit: u32, from_index: Option<u64>) -> async_graphql::Result<RankingsUpdate> {
+        let state = self.state.lock().await;
+        let (events, next_index) = Self::events_since(&state, from_index).await?;
+        let changed = events.iter().any(|event| !matches!(event, LoggedEvent::Registered(_) | LoggedEvent::PoolInjected(_)));
+        let rankings = if changed {
+            top_app_rankings(&state, limit as usize).await?
+        } else {
+            Vec::new()
+        };
+        Ok(RankingsUpdate { changed, rankings, next_index })
+    }
+
+    async fn balance_update(&self, owner: AccountOwner, from_index: Option<u64>) -> async_graphql::Result<BalanceUpdate> {
+        let state = self.state.lock().await;
+        let (events, next_index) = Self::events_since(&state, from_index).await?;
+        let changed = events.iter().any(|event| match event {
+            LoggedEvent::Registered(registered) => registered.owner == owner,
+            LoggedEvent::BetPlaced(bet) => bet.owner == owner,
+            LoggedEvent::Redeemed(redeem) => redeem.owner == owner,
+            LoggedEvent::Settled { .. } | LoggedEvent::AppRemoved(_) => true,
+            LoggedEvent::AppAdded(_) | LoggedEvent::PoolInjected(_) => false,
+        });
+        let balance = state.get_user_balance(&owner).await?;
+        Ok(BalanceUpdate { owner, changed, balance, next_index })
+    }
+}
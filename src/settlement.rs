@@ -0,0 +1,136 @@
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use serde::{Deserialize, Serialize};
+use crate::reward::RankedApp;
+use crate::types::{mul_div, EdgeConfig};
+use crate::EdgeError;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, async_graphql::SimpleObject)]
+pub struct AppSettlement {
+    pub app_id: String,
+    pub rank: u32,
+    pub base_reward: Amount,
+    pub bonus_percent: u64,
+    pub reward: Amount,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, async_graphql::SimpleObject)]
+pub struct UserPayout {
+    pub owner: AccountOwner,
+    pub app_id: String,
+    pub amount: Amount,
+}
+
+/// One supporter of an app at settlement time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BettorSnapshot {
+    pub owner: AccountOwner,
+    /// Current stake on the app.
+    pub stake: Amount,
+    /// Stake multiplied by the time it was held during the period; decides the payout share.
+    pub weight: u128,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppSnapshot {
+    pub app_id: String,
+    pub total_bet: Amount,
+    pub age_micros: u64,
+    pub bettors: Vec<BettorSnapshot>,
+}
+
+/// Everything a settlement needs to know, read from `EdgeState` up front.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlementSnapshot {
+    /// The ranked apps, best first, in the order `EdgeState::get_top_apps` gives.
+    pub apps: Vec<AppSnapshot>,
+    pub pool: Amount,
+}
+
+/// What a settlement pays out; applying it is left to the caller.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PayoutPlan {
+    pub apps: Vec<AppSettlement>,
+    pub payouts: Vec<UserPayout>,
+    pub pool_after: Amount,
+}
+
+/// Works out the payouts of one settlement.
+///
+/// The configured strategy splits `distribution_percent` of the ranked apps' total bet
+/// between them; if the pool cannot fund all rewards they are scaled down together.
+/// Within an app each bettor gets `reward * weight / total_weight`, rounded down, so the
+/// plan never pays out more than the pool.
+pub fn plan_settlement(config: &EdgeConfig, snapshot: &SettlementSnapshot) -> Result<PayoutPlan, EdgeError> {
+    let mut plan = PayoutPlan { pool_after: snapshot.pool, ..PayoutPlan::default() };
+    if snapshot.pool == Amount::ZERO {
+        return Ok(plan);
+    }
+
+    let strategy = config.reward_strategy();
+    let ranked = &snapshot.apps[..snapshot.apps.len().min(strategy.ranked_apps())];
+
+    let total_bets = ranked.iter().fold(0u128, |acc, app| acc.saturating_add(u128::from(app.total_bet)));
+    let distribution_amount = mul_div(total_bets, config.distribution_percent as u128, 100);
+    if distribution_amount == 0 {
+        return Ok(plan);
+    }
+
+    let mut eligible = Vec::new();
+    let mut ranked_apps = Vec::new();
+    for (rank, app) in ranked.iter().enumerate() {
+        let total_weight = app.bettors.iter().fold(0u128, |acc, bettor| acc.saturating_add(bettor.weight));
+        if total_weight == 0 {
+            continue;
+        }
+        ranked_apps.push(RankedApp {
+            rank,
+            stakes: app.bettors.iter()
+                .filter(|bettor| bettor.stake > Amount::ZERO)
+                .map(|bettor| u128::from(bettor.stake))
+                .collect(),
+            age_micros: app.age_micros,
+        });
+        eligible.push((rank, app, total_weight));
+    }
+    let planned = strategy.allocate(distribution_amount, &ranked_apps);
+
+    let requested = planned.iter().fold(0u128, |acc, app_reward| acc.saturating_add(app_reward.reward));
+    let available = u128::from(snapshot.pool);
+
+    let mut total_credited = 0u128;
+    for ((rank, app, total_weight), planned_reward) in eligible.into_iter().zip(planned) {
+        let reward = if requested > available {
+            mul_div(planned_reward.reward, available, requested)
+        } else {
+            planned_reward.reward
+        };
+
+        let mut app_reward = 0u128;
+        for bettor in &app.bettors {
+            let reward_share = mul_div(reward, bettor.weight, total_weight);
+            if reward_share == 0 {
+                continue;
+            }
+            app_reward += reward_share;
+            plan.payouts.push(UserPayout {
+                owner: bettor.owner,
+                app_id: app.app_id.clone(),
+                amount: Amount::from_attos(reward_share),
+            });
+        }
+
+        total_credited += app_reward;
+        plan.apps.push(AppSettlement {
+            app_id: app.app_id.clone(),
+            rank: (rank + 1) as u32,
+            base_reward: Amount::from_attos(planned_reward.base_reward),
+            bonus_percent: planned_reward.bonus_percent,
+            reward: Amount::from_attos(app_reward),
+        });
+    }
+
+    // Each share rounds down and the rewards were capped at the pool above, so the
+    // exact amount credited is always covered.
+    plan.pool_after = Amount::from_attos(available.checked_sub(total_credited).ok_or(EdgeError::PoolOverdrawn)?);
+    Ok(plan)
+}
@@ -1,9 +1,14 @@
-use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, View, ViewError};
+use linera_sdk::views::{linera_views, ByteMapView, CollectionView, LogView, MapView, RegisterView, RootView, View, ViewError};
 use linera_sdk::ViewStorageContext;
 use linera_sdk::views::linera_views::context::Context;
 use linera_sdk::linera_base_types::{AccountOwner, Timestamp, Amount};
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use crate::settlement::{AppSettlement, BettorSnapshot, UserPayout};
+use crate::types::{EdgeConfig, LeaderboardPeriod, PendingConfig, Role};
+use crate::{
+    AppAddedEvent, AppPayout, AppRemovedEvent, BetPlacedEvent, EdgeEvent, PoolInjectedEvent, RedeemedEvent,
+    RegisteredEvent, SettledEvent,
+};
 
 #[derive(Debug, Deserialize, Serialize, Clone, async_graphql::SimpleObject)]
 pub struct AppInfo {
@@ -14,118 +19,641 @@ pub struct AppInfo {
     pub is_active: bool,
 }
 
+/// Points accounting: everything minted must sit in a balance, a stake or the pool.
+#[derive(Debug, Deserialize, Serialize, Clone, async_graphql::SimpleObject)]
+pub struct SupplyAudit {
+    pub minted: Amount,
+    pub balances: Amount,
+    pub staked: Amount,
+    pub pool: Amount,
+    pub balanced: bool,
+}
+
+/// Receipt of one `Settle` run; `epoch` is its index in `EdgeState::settlements`.
+#[derive(Debug, Deserialize, Serialize, Clone, async_graphql::SimpleObject)]
+pub struct SettlementRecord {
+    pub epoch: u64,
+    pub timestamp: Timestamp,
+    pub pool_before: Amount,
+    pub pool_after: Amount,
+    pub apps: Vec<AppSettlement>,
+    pub payouts: Vec<UserPayout>,
+}
+
+impl SettlementRecord {
+    /// The `Settled` event announcing this settlement.
+    pub fn settled_event(&self) -> SettledEvent {
+        SettledEvent {
+            timestamp: self.timestamp,
+            distributed: self.pool_before.saturating_sub(self.pool_after),
+            pool_amount: self.pool_after,
+            payouts: self.apps.iter().map(|app| AppPayout {
+                app_id: app.app_id.clone(),
+                rank: app.rank,
+                reward: app.reward,
+            }).collect(),
+        }
+    }
+}
+
+/// What `EdgeState::events` keeps of an `EdgeEvent`. A settlement is kept as the epoch of
+/// its receipt in `EdgeState::settlements` instead of a second copy of its payouts.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum LoggedEvent {
+    Registered(RegisteredEvent),
+    BetPlaced(BetPlacedEvent),
+    Redeemed(RedeemedEvent),
+    Settled { epoch: u64 },
+    AppAdded(AppAddedEvent),
+    AppRemoved(AppRemovedEvent),
+    PoolInjected(PoolInjectedEvent),
+}
+
+/// Version of the storage layout written by this code; see `EdgeState::migrate`.
+pub const SCHEMA_VERSION: u32 = 11;
+
+/// Address the pre-role `set_owner` wrote into every admin whitelist; `migrate` drops it
+/// instead of granting it roles.
+const HARDCODED_LEGACY_ADMIN: &str = "0xa0916f957038344afff8c117b0a568562f73f0f2";
+
+/// Key of an app in `EdgeState::app_rankings`: the inverted total in big-endian order,
+/// then the app id, so keys sort by descending total and ties by app id.
+fn ranking_key(app_id: &str, total_bet: Amount) -> Vec<u8> {
+    let mut key = (u128::MAX - u128::from(total_bet)).to_be_bytes().to_vec();
+    key.extend_from_slice(app_id.as_bytes());
+    key
+}
+
+/// Most entries a leaderboard query returns, and the number of best earners archived when
+/// a period closes. Every earner keeps a rank in the leaderboard indexes.
+pub const LEADERBOARD_SIZE: usize = 100;
+
+/// Prefix shared by the leaderboard keys of the period with `index`; later periods sort first.
+fn leaderboard_prefix(index: u64) -> [u8; 8] {
+    (u64::MAX - index).to_be_bytes()
+}
+
+/// Key of a user in a leaderboard index: the period prefix, inverted earnings in big-endian
+/// order, then the owner, so the live period comes first, sorted by descending earnings.
+fn leaderboard_key(index: u64, owner: &AccountOwner, earnings: Amount) -> Vec<u8> {
+    let mut key = leaderboard_prefix(index).to_vec();
+    key.extend_from_slice(&(u128::MAX - u128::from(earnings)).to_be_bytes());
+    key.extend_from_slice(owner.to_string().as_bytes());
+    key
+}
+
+/// The `leaders` of the `period` with `index`, best first, as it is archived.
+fn final_leaderboard(period: LeaderboardPeriod, index: u64, leaders: Vec<(AccountOwner, Amount)>) -> ArchivedLeaderboard {
+    ArchivedLeaderboard {
+        period,
+        index,
+        starts_at: period.start_of(index),
+        ends_at: period.start_of(index + 1),
+        leaders: leaders
+            .into_iter()
+            .map(|(owner, earnings)| LeaderboardEntry { owner, earnings })
+            .collect(),
+    }
+}
+
+/// A user's earnings, tagged with the period they were made in. An entry from an earlier
+/// period than the live one reads as zero, so closing a period never touches user entries.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct PeriodEarnings {
+    /// See `LeaderboardPeriod::index_at`.
+    pub index: u64,
+    pub amount: Amount,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, async_graphql::SimpleObject)]
+pub struct LeaderboardEntry {
+    pub owner: AccountOwner,
+    pub earnings: Amount,
+}
+
+/// Final leaderboard of a closed period.
+#[derive(Debug, Deserialize, Serialize, Clone, async_graphql::SimpleObject)]
+pub struct ArchivedLeaderboard {
+    pub period: LeaderboardPeriod,
+    /// See `LeaderboardPeriod::index_at`.
+    pub index: u64,
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,
+    /// Best earners first, at most `LEADERBOARD_SIZE`.
+    pub leaders: Vec<LeaderboardEntry>,
+}
+
+/// One bet lot; a user's position on an app is the sum of its lots.
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct UserBet {
     pub app_id: String,
     pub amount: Amount,
     pub timestamp: Timestamp,
+    /// When the lot can be redeemed without penalty, under the lock in force when it was placed.
+    pub unlock_at: Timestamp,
+}
+
+/// A bet lot as stored before `SCHEMA_VERSION` 10, without its unlock time.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct LegacyUserBet {
+    pub app_id: String,
+    pub amount: Amount,
+    pub timestamp: Timestamp,
+}
+
+impl LegacyUserBet {
+    /// The lot, locked as `config` locks a bet placed at its timestamp.
+    fn with_unlock_at(self, config: &EdgeConfig) -> UserBet {
+        UserBet {
+            unlock_at: config.unlock_at(self.timestamp),
+            app_id: self.app_id,
+            amount: self.amount,
+            timestamp: self.timestamp,
+        }
+    }
 }
 
 #[derive(RootView)]
 #[view(context = ViewStorageContext)]
 pub struct EdgeState {
     pub user_balances: MapView<AccountOwner, Amount>,
-    pub user_bets: MapView<AccountOwner, Vec<UserBet>>,
+    /// Bet lots per user and app, oldest first. A user's entry is removed once all of
+    /// their positions are closed.
+    pub user_positions: CollectionView<AccountOwner, MapView<String, Vec<UserBet>>>,
+    /// Pre-`SCHEMA_VERSION` 10 layout of `user_positions`; emptied by `migrate`.
+    pub legacy_user_positions: CollectionView<AccountOwner, MapView<String, Vec<LegacyUserBet>>>,
+    /// Pre-`SCHEMA_VERSION` 1 layout with every lot of a user in one vector; emptied by `migrate`.
+    pub legacy_user_bets: MapView<AccountOwner, Vec<LegacyUserBet>>,
+    pub schema_version: RegisterView<u32>,
+    /// Reverse index of `user_positions`: open stake of each bettor per app.
+    pub app_bettors: CollectionView<String, MapView<AccountOwner, Amount>>,
+    /// Number of entries in each app's `app_bettors` map.
+    pub app_supporters: MapView<String, u32>,
     pub app_total_bets: MapView<String, Amount>,
     pub app_pool_contributions: MapView<String, Amount>,
+    /// Listed apps with their total bet, ordered best first; see `ranking_key`.
+    pub app_rankings: ByteMapView<(String, Amount)>,
     pub pool_amount: RegisterView<Amount>,
+    pub total_minted: RegisterView<Amount>,
     pub last_settle_time: RegisterView<Timestamp>,
     pub owner: RegisterView<Option<AccountOwner>>,
+    pub pending_owner: RegisterView<Option<AccountOwner>>,
     pub app_info: MapView<String, AppInfo>,
-    pub user_daily_earnings: MapView<AccountOwner, Amount>,
-    pub user_weekly_earnings: MapView<AccountOwner, Amount>,
-    pub user_monthly_earnings: MapView<AccountOwner, Amount>,
+    pub user_daily_earnings: MapView<AccountOwner, PeriodEarnings>,
+    pub user_weekly_earnings: MapView<AccountOwner, PeriodEarnings>,
+    pub user_monthly_earnings: MapView<AccountOwner, PeriodEarnings>,
+    /// Pre-`SCHEMA_VERSION` 5 layouts with untagged earnings; emptied by `migrate`.
+    pub legacy_user_daily_earnings: MapView<AccountOwner, Amount>,
+    pub legacy_user_weekly_earnings: MapView<AccountOwner, Amount>,
+    pub legacy_user_monthly_earnings: MapView<AccountOwner, Amount>,
+    /// The earnings maps above, grouped by period and ordered best first; see `leaderboard_key`.
+    /// An entry left from a closed period is removed when its user next earns.
+    pub daily_leaderboard: ByteMapView<(AccountOwner, Amount)>,
+    pub weekly_leaderboard: ByteMapView<(AccountOwner, Amount)>,
+    pub monthly_leaderboard: ByteMapView<(AccountOwner, Amount)>,
     pub last_daily_reset: RegisterView<Timestamp>,
     pub last_weekly_reset: RegisterView<Timestamp>,
     pub last_monthly_reset: RegisterView<Timestamp>,
-    pub whitelist: MapView<AccountOwner, bool>,
+    pub leaderboard_archive: MapView<(LeaderboardPeriod, u64), ArchivedLeaderboard>,
+    /// Earnings of closed periods, moved here from the earnings maps when the user next earns.
+    pub earnings_archive: MapView<(LeaderboardPeriod, u64, AccountOwner), Amount>,
+    pub roles: MapView<AccountOwner, Vec<Role>>,
+    /// Admin whitelist replaced by `roles`; emptied by `migrate`.
+    pub legacy_whitelist: MapView<AccountOwner, bool>,
+    pub config: RegisterView<EdgeConfig>,
+    pub pending_config: RegisterView<Option<PendingConfig>>,
+    pub events: LogView<LoggedEvent>,
+    pub settlements: LogView<SettlementRecord>,
 }
 
 impl EdgeState {
     pub fn create_empty(context: ViewStorageContext) -> Self {
         Self {
             user_balances: MapView::new(context.clone()).expect("Failed to create user_balances map"),
-            user_bets: MapView::new(context.clone()).expect("Failed to create user_bets map"),
+            user_positions: CollectionView::new(context.clone()).expect("Failed to create user_positions collection"),
+            legacy_user_positions: CollectionView::new(context.clone()).expect("Failed to create legacy_user_positions collection"),
+            legacy_user_bets: MapView::new(context.clone()).expect("Failed to create legacy_user_bets map"),
+            schema_version: RegisterView::new(context.clone()).expect("Failed to create schema_version register"),
+            app_bettors: CollectionView::new(context.clone()).expect("Failed to create app_bettors collection"),
+            app_supporters: MapView::new(context.clone()).expect("Failed to create app_supporters map"),
             app_total_bets: MapView::new(context.clone()).expect("Failed to create app_total_bets map"),
             app_pool_contributions: MapView::new(context.clone()).expect("Failed to create app_pool_contributions map"),
+            app_rankings: ByteMapView::new(context.clone()).expect("Failed to create app_rankings map"),
             pool_amount: RegisterView::new(context.clone()).expect("Failed to create pool_amount register"),
+            total_minted: RegisterView::new(context.clone()).expect("Failed to create total_minted register"),
             last_settle_time: RegisterView::new(context.clone()).expect("Failed to create last_settle_time register"),
             owner: RegisterView::new(context.clone()).expect("Failed to create owner register"),
+            pending_owner: RegisterView::new(context.clone()).expect("Failed to create pending_owner register"),
             app_info: MapView::new(context.clone()).expect("Failed to create app_info map"),
             user_daily_earnings: MapView::new(context.clone()).expect("Failed to create user_daily_earnings map"),
             user_weekly_earnings: MapView::new(context.clone()).expect("Failed to create user_weekly_earnings map"),
             user_monthly_earnings: MapView::new(context.clone()).expect("Failed to create user_monthly_earnings map"),
+            legacy_user_daily_earnings: MapView::new(context.clone()).expect("Failed to create legacy_user_daily_earnings map"),
+            legacy_user_weekly_earnings: MapView::new(context.clone()).expect("Failed to create legacy_user_weekly_earnings map"),
+            legacy_user_monthly_earnings: MapView::new(context.clone()).expect("Failed to create legacy_user_monthly_earnings map"),
+            daily_leaderboard: ByteMapView::new(context.clone()).expect("Failed to create daily_leaderboard map"),
+            weekly_leaderboard: ByteMapView::new(context.clone()).expect("Failed to create weekly_leaderboard map"),
+            monthly_leaderboard: ByteMapView::new(context.clone()).expect("Failed to create monthly_leaderboard map"),
             last_daily_reset: RegisterView::new(context.clone()).expect("Failed to create last_daily_reset register"),
             last_weekly_reset: RegisterView::new(context.clone()).expect("Failed to create last_weekly_reset register"),
             last_monthly_reset: RegisterView::new(context.clone()).expect("Failed to create last_monthly_reset register"),
-            whitelist: MapView::new(context.clone()).expect("Failed to create whitelist map"),
+            leaderboard_archive: MapView::new(context.clone()).expect("Failed to create leaderboard_archive map"),
+            earnings_archive: MapView::new(context.clone()).expect("Failed to create earnings_archive map"),
+            roles: MapView::new(context.clone()).expect("Failed to create roles map"),
+            legacy_whitelist: MapView::new(context.clone()).expect("Failed to create legacy_whitelist map"),
+            config: RegisterView::new(context.clone()).expect("Failed to create config register"),
+            pending_config: RegisterView::new(context.clone()).expect("Failed to create pending_config register"),
+            events: LogView::new(context.clone()).expect("Failed to create events log"),
+            settlements: LogView::new(context.clone()).expect("Failed to create settlements log"),
         }
     }
 
     pub async fn load(context: ViewStorageContext) -> Result<Self, ViewError> {
         let user_balances_context = context.clone_with_base_key(b"edge_user_balances".to_vec());
-        let user_bets_context = context.clone_with_base_key(b"edge_user_bets".to_vec());
+        let user_positions_context = context.clone_with_base_key(b"edge_user_lots".to_vec());
+        let legacy_user_positions_context = context.clone_with_base_key(b"edge_user_positions".to_vec());
+        let legacy_user_bets_context = context.clone_with_base_key(b"edge_user_bets".to_vec());
+        let schema_version_context = context.clone_with_base_key(b"edge_schema_version".to_vec());
+        let app_bettors_context = context.clone_with_base_key(b"edge_app_bettors".to_vec());
+        let app_supporters_context = context.clone_with_base_key(b"edge_app_supporters".to_vec());
         let app_total_bets_context = context.clone_with_base_key(b"edge_app_total_bets".to_vec());
         let app_pool_contributions_context = context.clone_with_base_key(b"edge_app_pool_contributions".to_vec());
+        let app_rankings_context = context.clone_with_base_key(b"edge_app_rankings".to_vec());
         let pool_amount_context = context.clone_with_base_key(b"edge_pool_amount".to_vec());
+        let total_minted_context = context.clone_with_base_key(b"edge_total_minted".to_vec());
         let last_settle_time_context = context.clone_with_base_key(b"edge_last_settle_time".to_vec());
         let owner_context = context.clone_with_base_key(b"edge_owner".to_vec());
+        let pending_owner_context = context.clone_with_base_key(b"edge_pending_owner".to_vec());
         let app_info_context = context.clone_with_base_key(b"edge_app_info".to_vec());
-        let user_daily_earnings_context = context.clone_with_base_key(b"edge_user_daily_earnings".to_vec());
-        let user_weekly_earnings_context = context.clone_with_base_key(b"edge_user_weekly_earnings".to_vec());
-        let user_monthly_earnings_context = context.clone_with_base_key(b"edge_user_monthly_earnings".to_vec());
+        let user_daily_earnings_context = context.clone_with_base_key(b"edge_user_daily_period_earnings".to_vec());
+        let user_weekly_earnings_context = context.clone_with_base_key(b"edge_user_weekly_period_earnings".to_vec());
+        let user_monthly_earnings_context = context.clone_with_base_key(b"edge_user_monthly_period_earnings".to_vec());
+        let legacy_user_daily_earnings_context = context.clone_with_base_key(b"edge_user_daily_earnings".to_vec());
+        let legacy_user_weekly_earnings_context = context.clone_with_base_key(b"edge_user_weekly_earnings".to_vec());
+        let legacy_user_monthly_earnings_context = context.clone_with_base_key(b"edge_user_monthly_earnings".to_vec());
+        let daily_leaderboard_context = context.clone_with_base_key(b"edge_daily_leaderboard".to_vec());
+        let weekly_leaderboard_context = context.clone_with_base_key(b"edge_weekly_leaderboard".to_vec());
+        let monthly_leaderboard_context = context.clone_with_base_key(b"edge_monthly_leaderboard".to_vec());
         let last_daily_reset_context = context.clone_with_base_key(b"edge_last_daily_reset".to_vec());
         let last_weekly_reset_context = context.clone_with_base_key(b"edge_last_weekly_reset".to_vec());
         let last_monthly_reset_context = context.clone_with_base_key(b"edge_last_monthly_reset".to_vec());
-        let whitelist_context = context.clone_with_base_key(b"edge_whitelist".to_vec());
+        let leaderboard_archive_context = context.clone_with_base_key(b"edge_leaderboard_archive".to_vec());
+        let earnings_archive_context = context.clone_with_base_key(b"edge_earnings_archive".to_vec());
+        let roles_context = context.clone_with_base_key(b"edge_roles".to_vec());
+        let legacy_whitelist_context = context.clone_with_base_key(b"edge_whitelist".to_vec());
+        let config_context = context.clone_with_base_key(b"edge_config".to_vec());
+        let pending_config_context = context.clone_with_base_key(b"edge_pending_config".to_vec());
+        let events_context = context.clone_with_base_key(b"edge_events".to_vec());
+        let settlements_context = context.clone_with_base_key(b"edge_settlements".to_vec());
 
         let user_balances = MapView::load(user_balances_context).await?;
-        let user_bets = MapView::load(user_bets_context).await?;
+        let user_positions = CollectionView::load(user_positions_context).await?;
+        let legacy_user_positions = CollectionView::load(legacy_user_positions_context).await?;
+        let legacy_user_bets = MapView::load(legacy_user_bets_context).await?;
+        let schema_version = RegisterView::load(schema_version_context).await?;
+        let app_bettors = CollectionView::load(app_bettors_context).await?;
+        let app_supporters = MapView::load(app_supporters_context).await?;
         let app_total_bets = MapView::load(app_total_bets_context).await?;
         let app_pool_contributions = MapView::load(app_pool_contributions_context).await?;
+        let app_rankings = ByteMapView::load(app_rankings_context).await?;
         let pool_amount = RegisterView::load(pool_amount_context).await?;
+        let total_minted = RegisterView::load(total_minted_context).await?;
         let last_settle_time = RegisterView::load(last_settle_time_context).await?;
         let owner = RegisterView::load(owner_context).await?;
+        let pending_owner = RegisterView::load(pending_owner_context).await?;
         let app_info = MapView::load(app_info_context).await?;
         let user_daily_earnings = MapView::load(user_daily_earnings_context).await?;
         let user_weekly_earnings = MapView::load(user_weekly_earnings_context).await?;
         let user_monthly_earnings = MapView::load(user_monthly_earnings_context).await?;
+        let legacy_user_daily_earnings = MapView::load(legacy_user_daily_earnings_context).await?;
+        let legacy_user_weekly_earnings = MapView::load(legacy_user_weekly_earnings_context).await?;
+        let legacy_user_monthly_earnings = MapView::load(legacy_user_monthly_earnings_context).await?;
+        let daily_leaderboard = ByteMapView::load(daily_leaderboard_context).await?;
+        let weekly_leaderboard = ByteMapView::load(weekly_leaderboard_context).await?;
+        let monthly_leaderboard = ByteMapView::load(monthly_leaderboard_context).await?;
         let last_daily_reset = RegisterView::load(last_daily_reset_context).await?;
         let last_weekly_reset = RegisterView::load(last_weekly_reset_context).await?;
         let last_monthly_reset = RegisterView::load(last_monthly_reset_context).await?;
-        let whitelist = MapView::load(whitelist_context).await?;
+        let leaderboard_archive = MapView::load(leaderboard_archive_context).await?;
+        let earnings_archive = MapView::load(earnings_archive_context).await?;
+        let roles = MapView::load(roles_context).await?;
+        let legacy_whitelist = MapView::load(legacy_whitelist_context).await?;
+        let config = RegisterView::load(config_context).await?;
+        let pending_config = RegisterView::load(pending_config_context).await?;
+        let events = LogView::load(events_context).await?;
+        let settlements = LogView::load(settlements_context).await?;
 
         Ok(Self {
             user_balances,
-            user_bets,
+            user_positions,
+            legacy_user_positions,
+            legacy_user_bets,
+            schema_version,
+            app_bettors,
+            app_supporters,
             app_total_bets,
             app_pool_contributions,
+            app_rankings,
             pool_amount,
+            total_minted,
             last_settle_time,
             owner,
+            pending_owner,
             app_info,
             user_daily_earnings,
             user_weekly_earnings,
             user_monthly_earnings,
+            legacy_user_daily_earnings,
+            legacy_user_weekly_earnings,
+            legacy_user_monthly_earnings,
+            daily_leaderboard,
+            weekly_leaderboard,
+            monthly_leaderboard,
             last_daily_reset,
             last_weekly_reset,
             last_monthly_reset,
-            whitelist,
+            leaderboard_archive,
+            earnings_archive,
+            roles,
+            legacy_whitelist,
+            config,
+            pending_config,
+            events,
+            settlements,
         })
     }
 
-    pub async fn initialize_user_balance(&mut self, owner: &AccountOwner) -> Result<(), ViewError> {
-        if !self.user_balances.contains_key(owner).await? {
-            self.user_balances.insert(owner, Amount::from_str("50").unwrap())?;
+    /// Whether storage was written by an older version of the contract.
+    pub fn needs_migration(&self) -> bool {
+        *self.schema_version.get() < SCHEMA_VERSION
+    }
+
+    /// Brings storage written by an older version of the contract up to `SCHEMA_VERSION`.
+    /// Runs at most once per layout change; later calls only read the version.
+    /// `application_owner` is the application's own id and `chain_owner` an owner of the
+    /// chain it runs on; see `propose_chain_owner`.
+    pub async fn migrate(&mut self, application_owner: AccountOwner, chain_owner: Option<AccountOwner>) -> Result<(), ViewError> {
+        let version = *self.schema_version.get();
+        if version >= SCHEMA_VERSION {
+            return Ok(());
+        }
+        
+        if version < 1 {
+            self.migrate_legacy_user_bets().await?;
+        } else if version < 10 {
+            // The steps below read `user_positions`, so lots move out of the version 1 to 9
+            // layout first.
+            self.migrate_legacy_positions().await?;
+        }
+        if version < 2 {
+            self.rebuild_app_bettor_index().await?;
+        }
+        if version < 3 {
+            self.rebuild_app_rankings().await?;
+        }
+        if version < 5 {
+            self.migrate_legacy_earnings().await?;
+        }
+        if version < 6 {
+            self.migrate_legacy_whitelist().await?;
+        }
+        if version < 7 {
+            self.backfill_total_minted().await?;
+        }
+        if version < 9 {
+            self.propose_chain_owner(application_owner, chain_owner).await?;
+        }
+        // Versions 8 to 10 kept only the best earners in the leaderboard indexes, and the
+        // version 5 step leaves them empty.
+        if version < 11 {
+            self.rebuild_leaderboards().await?;
+        }
+        
+        self.schema_version.set(SCHEMA_VERSION);
+        Ok(())
+    }
+
+    /// Moves every lot of the single-vector `legacy_user_bets` layout into `user_positions`.
+    /// Lots did not record their lock, so they get the one of the config in force now.
+    async fn migrate_legacy_user_bets(&mut self) -> Result<(), ViewError> {
+        let config = self.config.get().clone();
+        let mut legacy = Vec::new();
+        self.legacy_user_bets
+            .for_each_index_value(|owner, bets| {
+                legacy.push((owner.clone(), bets.into_owned()));
+                Ok(())
+            })
+            .await?;
+        for (owner, bets) in legacy {
+            for bet in bets {
+                if bet.amount == Amount::ZERO {
+                    continue;
+                }
+                let app_id = bet.app_id.clone();
+                let mut lots = self.get_user_app_bets(&owner, &app_id).await?;
+                lots.push(bet.with_unlock_at(&config));
+                self.set_user_app_bets(&owner, &app_id, lots).await?;
+            }
+        }
+        self.legacy_user_bets.clear();
+        Ok(())
+    }
+
+    /// Moves every lot of `legacy_user_positions` into `user_positions`. Lots did not record
+    /// their lock, so they get the one of the config in force now.
+    async fn migrate_legacy_positions(&mut self) -> Result<(), ViewError> {
+        let config = self.config.get().clone();
+        let mut legacy = Vec::new();
+        for owner in self.legacy_user_positions.indices().await? {
+            if let Some(positions) = self.legacy_user_positions.try_load_entry(&owner).await? {
+                positions
+                    .for_each_index_value(|app_id, lots| {
+                        legacy.push((owner.clone(), app_id, lots.into_owned()));
+                        Ok(())
+                    })
+                    .await?;
+            }
+        }
+        self.legacy_user_positions.clear();
+        
+        for (owner, app_id, lots) in legacy {
+            let lots: Vec<UserBet> = lots.into_iter().map(|lot| lot.with_unlock_at(&config)).collect();
+            let positions = self.user_positions.load_entry_mut(&owner).await?;
+            positions.insert(&app_id, lots)?;
+        }
+        Ok(())
+    }
+
+    /// Recomputes `app_bettors` and `app_supporters` from `user_positions`.
+    async fn rebuild_app_bettor_index(&mut self) -> Result<(), ViewError> {
+        self.app_bettors.clear();
+        self.app_supporters.clear();
+        
+        let mut stakes = Vec::new();
+        for owner in self.user_positions.indices().await? {
+            if let Some(positions) = self.user_positions.try_load_entry(&owner).await? {
+                positions
+                    .for_each_index_value(|app_id, lots| {
+                        let stake = lots.iter().fold(Amount::ZERO, |acc, bet| acc.saturating_add(bet.amount));
+                        stakes.push((owner.clone(), app_id, stake));
+                        Ok(())
+                    })
+                    .await?;
+            }
+        }
+        for (owner, app_id, stake) in stakes {
+            self.update_app_bettor_index(&owner, &app_id, stake).await?;
+        }
+        Ok(())
+    }
+
+    /// Credits the starting grant to a new account and records it as minted. Callers check
+    /// `is_registered` first, so the grant is minted once per account.
+    pub async fn register_user(&mut self, owner: &AccountOwner) -> Result<Amount, ViewError> {
+        let initial_balance = self.config.get().initial_balance;
+        self.user_balances.insert(owner, initial_balance)?;
+        self.record_mint(initial_balance).await?;
+        Ok(initial_balance)
+    }
+
+    /// Whether `owner` has claimed the starting grant; every account with a balance entry has.
+    pub async fn is_registered(&self, owner: &AccountOwner) -> Result<bool, ViewError> {
+        self.user_balances.contains_key(owner).await
+    }
+
+    pub async fn record_mint(&mut self, amount: Amount) -> Result<(), ViewError> {
+        let minted = self.total_minted.get().saturating_add(amount);
+        self.total_minted.set(minted);
+        Ok(())
+    }
+
+    /// Checks that balances, open stakes and the pool add up to everything ever minted.
+    /// Walks every balance and app total, so it is meant for the service, not the contract.
+    pub async fn audit_supply(&self) -> Result<SupplyAudit, ViewError> {
+        let mut balances = Amount::ZERO;
+        self.user_balances
+            .for_each_index_value(|_owner, balance| {
+                balances = balances.saturating_add(*balance);
+                Ok(())
+            })
+            .await?;
+        
+        let mut staked = Amount::ZERO;
+        self.app_total_bets
+            .for_each_index_value(|_app_id, total_bet| {
+                staked = staked.saturating_add(*total_bet);
+                Ok(())
+            })
+            .await?;
+        
+        let minted = *self.total_minted.get();
+        let pool = *self.pool_amount.get();
+        let balanced = balances.saturating_add(staked).saturating_add(pool) == minted;
+        
+        Ok(SupplyAudit { minted, balances, staked, pool, balanced })
+    }
+
+    pub async fn get_config(&self) -> Result<EdgeConfig, ViewError> {
+        Ok(self.config.get().clone())
+    }
+
+    pub async fn set_config(&mut self, config: EdgeConfig) -> Result<(), ViewError> {
+        self.config.set(config);
+        Ok(())
+    }
+
+    /// Returns the config in force at `current_time`, including a pending update whose
+    /// timelock has expired but which no operation has applied yet.
+    pub async fn get_active_config(&self, current_time: Timestamp) -> Result<EdgeConfig, ViewError> {
+        match self.pending_config.get() {
+            Some(pending) if pending.effective_at <= current_time => Ok(pending.config.clone()),
+            _ => Ok(self.config.get().clone()),
         }
+    }
+
+    pub async fn get_pending_config(&self) -> Result<Option<PendingConfig>, ViewError> {
+        Ok(self.pending_config.get().clone())
+    }
+
+    pub async fn schedule_config(&mut self, config: EdgeConfig, effective_at: Timestamp) -> Result<(), ViewError> {
+        self.pending_config.set(Some(PendingConfig { config, effective_at }));
         Ok(())
     }
+
+    /// Promotes the pending config once its timelock has expired.
+    pub async fn apply_pending_config(&mut self, current_time: Timestamp) -> Result<bool, ViewError> {
+        let pending = match self.pending_config.get() {
+            Some(pending) if pending.effective_at <= current_time => pending.config.clone(),
+            _ => return Ok(false),
+        };
+        self.config.set(pending);
+        self.pending_config.set(None);
+        Ok(true)
+    }
     
+    fn normalize_address(address: &AccountOwner) -> Result<AccountOwner, ViewError> {
+        address.to_string().to_lowercase().parse()
+            .map_err(|_| ViewError::NotFound("Failed to parse address for role lookup".to_string()))
+    }
+
+    pub async fn is_owner(&self, address: &AccountOwner) -> Result<bool, ViewError> {
+        Ok(self.owner.get().as_ref() == Some(address))
+    }
+
+    pub async fn get_roles(&self, address: &AccountOwner) -> Result<Vec<Role>, ViewError> {
+        let address_lowercase = Self::normalize_address(address)?;
+        Ok(self.roles.get(&address_lowercase).await?.unwrap_or_default())
+    }
+
+    /// Whether `address` may act as `role`; the owner holds every role.
+    pub async fn has_role(&self, address: &AccountOwner, role: Role) -> Result<bool, ViewError> {
+        if self.is_owner(address).await? {
+            return Ok(true);
+        }
+        Ok(self.get_roles(address).await?.contains(&role))
+    }
+
+    /// Whether `address` is the owner or holds any administrative role.
     pub async fn is_whitelisted(&self, address: &AccountOwner) -> Result<bool, ViewError> {
-        let address_lowercase: AccountOwner = address.to_string().to_lowercase().parse()
-            .map_err(|_| ViewError::NotFound("Failed to parse address for whitelist check".to_string()))?;
-        let is_whitelisted = self.whitelist.get(&address_lowercase).await?.unwrap_or(false);
-        Ok(is_whitelisted)
+        if self.is_owner(address).await? {
+            return Ok(true);
+        }
+        Ok(!self.get_roles(address).await?.is_empty())
+    }
+
+    pub async fn grant_role(&mut self, address: &AccountOwner, role: Role) -> Result<(), ViewError> {
+        let address_lowercase = Self::normalize_address(address)?;
+        let mut roles = self.roles.get(&address_lowercase).await?.unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role);
+            self.roles.insert(&address_lowercase, roles)?;
+        }
+        Ok(())
+    }
+
+    pub async fn revoke_role(&mut self, address: &AccountOwner, role: Role) -> Result<(), ViewError> {
+        let address_lowercase = Self::normalize_address(address)?;
+        let mut roles = self.roles.get(&address_lowercase).await?.unwrap_or_default();
+        roles.retain(|held| *held != role);
+        if roles.is_empty() {
+            self.roles.remove(&address_lowercase)?;
+        } else {
+            self.roles.insert(&address_lowercase, roles)?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_role_members(&self, role: Role) -> Result<Vec<AccountOwner>, ViewError> {
+        let mut members = Vec::new();
+        self.roles
+            .for_each_index_value(|address, roles| {
+                if roles.contains(&role) {
+                    members.push(address.clone());
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(members)
     }
 
     pub async fn get_user_balance(&self, owner: &AccountOwner) -> Result<Amount, ViewError> {
@@ -135,68 +663,210 @@ impl EdgeState {
         }
     }
 
-    pub async fn get_user_balance_with_initialization(&mut self, owner: &AccountOwner) -> Result<Amount, ViewError> {
-        let balance = self.user_balances.get(owner).await?;
-        match balance {
-            Some(balance) => Ok(balance),
-            None => {
-                let initial_balance = Amount::from_str("50").unwrap();
-                self.user_balances.insert(owner, initial_balance)?;
-                match self.user_balances.get(owner).await? {
-                    Some(balance) => Ok(balance),
-                    None => {
-                        Ok(initial_balance)
-                    }
+    pub async fn update_user_balance(&mut self, owner: &AccountOwner, amount: Amount) -> Result<(), ViewError> {
+        self.user_balances.insert(owner, amount)?;
+        Ok(())
+    }
+
+    pub async fn get_user_app_bet(&self, owner: &AccountOwner, app_id: &str) -> Result<Amount, ViewError> {
+        let total_amount = self.get_user_app_bets(owner, app_id).await?
+            .iter()
+            .fold(Amount::ZERO, |acc, bet| acc.saturating_add(bet.amount));
+        Ok(total_amount)
+    }
+
+    pub async fn get_all_user_bets(&self, owner: &AccountOwner) -> Result<Vec<UserBet>, ViewError> {
+        let mut user_bets = Vec::new();
+        if let Some(positions) = self.user_positions.try_load_entry(owner).await? {
+            positions
+                .for_each_index_value(|_app_id, lots| {
+                    user_bets.extend(lots.iter().cloned());
+                    Ok(())
+                })
+                .await?;
+        }
+        Ok(user_bets)
+    }
+
+    /// The user's lots on `app_id`, oldest first.
+    pub async fn get_user_app_bets(&self, owner: &AccountOwner, app_id: &str) -> Result<Vec<UserBet>, ViewError> {
+        match self.user_positions.try_load_entry(owner).await? {
+            Some(positions) => Ok(positions.get(app_id).await?.unwrap_or_default()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replaces the user's lots on `app_id`, dropping the position (and the user's entry
+    /// once it holds no position) when `lots` is empty.
+    async fn set_user_app_bets(&mut self, owner: &AccountOwner, app_id: &str, lots: Vec<UserBet>) -> Result<(), ViewError> {
+        let stake = lots.iter().fold(Amount::ZERO, |acc, bet| acc.saturating_add(bet.amount));
+        let positions = self.user_positions.load_entry_mut(owner).await?;
+        if lots.is_empty() {
+            positions.remove(app_id)?;
+        } else {
+            positions.insert(app_id, lots)?;
+        }
+        
+        if positions.count().await? == 0 {
+            self.user_positions.remove_entry(owner)?;
+        }
+        
+        self.update_app_bettor_index(owner, app_id, stake).await
+    }
+
+    /// Recomputes `app_rankings` from the listed apps and their totals.
+    async fn rebuild_app_rankings(&mut self) -> Result<(), ViewError> {
+        self.app_rankings.clear();
+        
+        let mut listed = Vec::new();
+        self.app_info
+            .for_each_index_value(|app_id, app_info| {
+                if app_info.is_active {
+                    listed.push(app_id.clone());
                 }
+                Ok(())
+            })
+            .await?;
+        for app_id in listed {
+            let total_bet = self.get_app_total_bet(&app_id).await?;
+            self.app_rankings.insert(ranking_key(&app_id, total_bet), (app_id, total_bet));
+        }
+        Ok(())
+    }
+
+    /// Grants every role to each address of the legacy admin whitelist, which allowed all
+    /// administrative operations. Skips the owner, who holds every role anyway, and the
+    /// address the old `set_owner` hard-coded, which no owner chose.
+    async fn migrate_legacy_whitelist(&mut self) -> Result<(), ViewError> {
+        let owner = self.owner.get().clone();
+        let mut admins = Vec::new();
+        self.legacy_whitelist
+            .for_each_index_value(|address, whitelisted| {
+                let hardcoded = address.to_string().to_lowercase() == HARDCODED_LEGACY_ADMIN;
+                if *whitelisted && !hardcoded && owner.as_ref() != Some(&address) {
+                    admins.push(address.clone());
+                }
+                Ok(())
+            })
+            .await?;
+        self.legacy_whitelist.clear();
+        
+        for address in admins {
+            for role in Role::ALL {
+                self.grant_role(&address, role).await?;
             }
         }
+        Ok(())
     }
 
-    pub async fn update_user_balance(&mut self, owner: &AccountOwner, amount: Amount) -> Result<(), ViewError> {
-        self.user_balances.insert(owner, amount)?;
+    /// A deployment instantiated without a signer is owned by its application id, which no
+    /// account can sign as, so no owner-only operation could ever run. Proposes `chain_owner`
+    /// as the new owner so they can take over with `AcceptOwnership`.
+    pub async fn propose_chain_owner(&mut self, application_owner: AccountOwner, chain_owner: Option<AccountOwner>) -> Result<(), ViewError> {
+        if self.owner.get().as_ref() != Some(&application_owner) || self.pending_owner.get().is_some() {
+            return Ok(());
+        }
+        self.pending_owner.set(chain_owner);
         Ok(())
     }
 
-    pub async fn get_user_app_bet(&self, owner: &AccountOwner, app_id: &str) -> Result<Amount, ViewError> {
-        match self.user_bets.get(owner).await? {
-            Some(user_bets) => {
-                let total_amount = user_bets.iter()
-                    .filter(|bet| bet.app_id == app_id)
-                    .fold(Amount::ZERO, |acc, bet| acc.saturating_add(bet.amount));
-                Ok(total_amount)
-            },
-            None => Ok(Amount::ZERO),
+    /// Deployments from before supply tracking never counted their mints; treat every point
+    /// they hold as minted so `audit_supply` starts out balanced.
+    async fn backfill_total_minted(&mut self) -> Result<(), ViewError> {
+        if *self.total_minted.get() != Amount::ZERO {
+            return Ok(());
         }
+        let audit = self.audit_supply().await?;
+        self.total_minted.set(audit.balances.saturating_add(audit.staked).saturating_add(audit.pool));
+        Ok(())
     }
 
-    pub async fn get_all_user_bets(&self, owner: &AccountOwner) -> Result<Vec<UserBet>, ViewError> {
-        match self.user_bets.get(owner).await? {
-            Some(user_bets) => {
-                Ok(user_bets.clone())
+    /// Tags the untagged earnings of the legacy maps with the live period of each kind.
+    async fn migrate_legacy_earnings(&mut self) -> Result<(), ViewError> {
+        for period in LeaderboardPeriod::ALL {
+            let index = self.get_live_period_index(period).await?;
+            let legacy = match period {
+                LeaderboardPeriod::Daily => &mut self.legacy_user_daily_earnings,
+                LeaderboardPeriod::Weekly => &mut self.legacy_user_weekly_earnings,
+                LeaderboardPeriod::Monthly => &mut self.legacy_user_monthly_earnings,
+            };
+            let mut entries = Vec::new();
+            legacy
+                .for_each_index_value(|owner, amount| {
+                    entries.push((owner.clone(), *amount));
+                    Ok(())
+                })
+                .await?;
+            legacy.clear();
+            
+            let (earnings_map, _) = self.period_views_mut(period);
+            for (owner, amount) in entries {
+                earnings_map.insert(&owner, PeriodEarnings { index, amount })?;
             }
-            None => Ok(Vec::new()),
         }
+        Ok(())
     }
 
-    pub async fn update_user_bet(&mut self, owner: &AccountOwner, app_id: String, amount: Amount, timestamp: Timestamp) -> Result<(), ViewError> {
-        let mut user_bets = match self.user_bets.get(owner).await? {
-            Some(bets) => bets.clone(),
-            None => Vec::new(),
-        };
+    /// Recomputes the leaderboard indexes from the earnings maps.
+    async fn rebuild_leaderboards(&mut self) -> Result<(), ViewError> {
+        for period in LeaderboardPeriod::ALL {
+            let (earnings_map, leaderboard) = self.period_views_mut(period);
+            leaderboard.clear();
+            
+            let mut entries = Vec::new();
+            earnings_map
+                .for_each_index_value(|owner, earnings| {
+                    entries.push((owner.clone(), *earnings));
+                    Ok(())
+                })
+                .await?;
+            for (owner, earnings) in entries {
+                leaderboard.insert(leaderboard_key(earnings.index, &owner, earnings.amount), (owner, earnings.amount));
+            }
+        }
+        Ok(())
+    }
+
+    /// Records `owner`'s open stake on `app_id` in `app_bettors`, keeping `app_supporters` in step.
+    async fn update_app_bettor_index(&mut self, owner: &AccountOwner, app_id: &str, stake: Amount) -> Result<(), ViewError> {
+        let app_id = app_id.to_string();
+        let bettors = self.app_bettors.load_entry_mut(&app_id).await?;
+        let was_supporter = bettors.contains_key(owner).await?;
         
-        if let Some(existing_bet) = user_bets.iter_mut().find(|bet| bet.app_id == app_id) {
-            existing_bet.amount = amount;
-        } else {
-            user_bets.push(UserBet {
-                app_id,
-                amount,
-                timestamp
-            });
+        let supporters = self.app_supporters.get(&app_id).await?.unwrap_or(0);
+        if stake > Amount::ZERO {
+            bettors.insert(owner, stake)?;
+            if !was_supporter {
+                self.app_supporters.insert(&app_id, supporters + 1)?;
+            }
+        } else if was_supporter {
+            bettors.remove(owner)?;
+            if supporters <= 1 {
+                self.app_supporters.remove(&app_id)?;
+                self.app_bettors.remove_entry(&app_id)?;
+            } else {
+                self.app_supporters.insert(&app_id, supporters - 1)?;
+            }
         }
+        Ok(())
+    }
+
+    /// Takes `amount` out of the user's lots on `app_id`, oldest lots first.
+    pub async fn redeem_user_bet(&mut self, owner: &AccountOwner, app_id: &str, amount: Amount) -> Result<(), ViewError> {
+        let mut lots = self.get_user_app_bets(owner, app_id).await?;
         
-        self.user_bets.insert(owner, user_bets)?;
+        let mut remaining = amount;
+        for bet in lots.iter_mut() {
+            if remaining == Amount::ZERO {
+                break;
+            }
+            let taken = std::cmp::min(bet.amount, remaining);
+            bet.amount = bet.amount.saturating_sub(taken);
+            remaining = remaining.saturating_sub(taken);
+        }
+        lots.retain(|bet| bet.amount > Amount::ZERO);
         
-        Ok(())
+        self.set_user_app_bets(owner, app_id, lots).await
     }
 
     pub async fn get_app_total_bet(&self, app_id: &str) -> Result<Amount, ViewError> {
@@ -206,7 +876,20 @@ impl EdgeState {
         }
     }
 
+    /// Sets the app's total bet and moves it to its new place in `app_rankings`. An app
+    /// enters the index only while it is listed and active, so the totals that bettors of
+    /// an app deleted by the old `RemoveApplication` still redeem from never take a top slot.
     pub async fn update_app_total_bet(&mut self, app_id: String, amount: Amount) -> Result<(), ViewError> {
+        let mut ranked = false;
+        if let Some(previous) = self.app_total_bets.get(&app_id).await? {
+            let previous_key = ranking_key(&app_id, previous);
+            ranked = self.app_rankings.get(&previous_key).await?.is_some();
+            self.app_rankings.remove(previous_key);
+        }
+        let listed = matches!(self.app_info.get(&app_id).await?, Some(app_info) if app_info.is_active);
+        if ranked || listed {
+            self.app_rankings.insert(ranking_key(&app_id, amount), (app_id.clone(), amount));
+        }
         self.app_total_bets.insert(&app_id, amount)?;
         Ok(())
     }
@@ -248,25 +931,36 @@ impl EdgeState {
     pub async fn set_owner(&mut self, owner: AccountOwner) -> Result<(), ViewError> {
         self.owner.set(Some(owner.clone()));
 
-        let owner_lowercase = owner.to_string().to_lowercase().parse()
-            .map_err(|_| ViewError::NotFound("Failed to parse owner address".to_string()))?;
-        self.whitelist.insert(&owner_lowercase, true)?;
+        self.save().await?;
+        Ok(())
+    }
+
+    pub async fn get_pending_owner(&self) -> Result<Option<AccountOwner>, ViewError> {
+        Ok(self.pending_owner.get().clone())
+    }
 
-        let user_address = "0xa0916f957038344afff8c117b0a568562f73f0f2";
-        let user_address_lowercase = user_address.to_lowercase().parse()
-            .map_err(|_| ViewError::NotFound("Failed to parse user address".to_string()))?;
-        self.whitelist.insert(&user_address_lowercase, true)?;
+    pub async fn set_pending_owner(&mut self, pending_owner: Option<AccountOwner>) -> Result<(), ViewError> {
+        self.pending_owner.set(pending_owner);
+        Ok(())
+    }
 
-        self.save().await?;
+    /// Hands ownership to the pending owner and clears the proposal.
+    pub async fn accept_ownership(&mut self, new_owner: AccountOwner) -> Result<(), ViewError> {
+        self.owner.set(Some(new_owner));
+        self.pending_owner.set(None);
         Ok(())
     }
 
-    pub async fn get_all_app_totals(&self) -> Result<Vec<(String, Amount)>, ViewError> {
+    /// The `limit` listed apps with the largest total bet, best first, read from `app_rankings`.
+    pub async fn get_top_apps(&self, limit: usize) -> Result<Vec<(String, Amount)>, ViewError> {
         let mut apps = Vec::new();
-        self.app_total_bets
-            .for_each_index_value(|app_id, total_bet| {
-                apps.push((app_id.clone(), *total_bet));
-                Ok(())
+        if limit == 0 {
+            return Ok(apps);
+        }
+        self.app_rankings
+            .for_each_key_value_while(|_key, entry| {
+                apps.push(entry.into_owned());
+                Ok(apps.len() < limit)
             })
             .await?;
         Ok(apps)
@@ -290,76 +984,124 @@ impl EdgeState {
         Ok(())
     }
 
+    pub async fn set_app_active(&mut self, app_id: &str, is_active: bool) -> Result<(), ViewError> {
+        if let Some(mut app_info) = self.app_info.get(app_id).await? {
+            app_info.is_active = is_active;
+            self.app_info.insert(app_id, app_info)?;
+        }
+        Ok(())
+    }
+
+    /// Drops the per-app betting totals once every position on the app has been closed.
+    pub async fn clear_app_totals(&mut self, app_id: &str) -> Result<(), ViewError> {
+        if let Some(total_bet) = self.app_total_bets.get(app_id).await? {
+            self.app_rankings.remove(ranking_key(app_id, total_bet));
+        }
+        self.app_total_bets.remove(app_id)?;
+        self.app_pool_contributions.remove(app_id)?;
+        Ok(())
+    }
+
+    /// Lots on `app_id` of every user holding a position on it.
+    async fn get_app_positions(&self, app_id: &str) -> Result<Vec<(AccountOwner, Vec<UserBet>)>, ViewError> {
+        let mut positions = Vec::new();
+        for (owner, _) in self.get_app_bettors(app_id).await? {
+            let lots = self.get_user_app_bets(&owner, app_id).await?;
+            positions.push((owner, lots));
+        }
+        Ok(positions)
+    }
+
+    /// Open stake of every bettor on `app_id`, read from the `app_bettors` index.
     pub async fn get_app_bettors(&self, app_id: &str) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
         let mut bettors = Vec::new();
         
-        self.user_bets
-            .for_each_index_value(|owner, user_bets| {
-                let total_amount = user_bets.iter()
-                    .filter(|bet| bet.app_id == app_id)
-                    .fold(Amount::ZERO, |acc, bet| acc.saturating_add(bet.amount));
-                
-                if total_amount > Amount::ZERO {
-                    bettors.push((owner.clone(), total_amount));
-                }
-                Ok(())
-            })
-            .await?;
+        if let Some(app_bettors) = self.app_bettors.try_load_entry(app_id).await? {
+            app_bettors
+                .for_each_index_value(|owner, stake| {
+                    bettors.push((owner.clone(), *stake));
+                    Ok(())
+                })
+                .await?;
+        }
+        
+        Ok(bettors)
+    }
+    
+    /// Stake of every bettor on `app_id`, and that stake weighted by how long each lot was
+    /// held since `period_start`.
+    ///
+    /// A lot contributes `amount * (now - max(timestamp, period_start))` to the weight, so a
+    /// bet placed right before settling earns next to nothing for that period.
+    pub async fn get_app_bettor_snapshots(&self, app_id: &str, period_start: Timestamp, now: Timestamp) -> Result<Vec<BettorSnapshot>, ViewError> {
+        let mut bettors = Vec::new();
+        
+        for (owner, lots) in self.get_app_positions(app_id).await? {
+            let mut stake = Amount::ZERO;
+            let mut weight = 0u128;
+            for bet in &lots {
+                let held_since = std::cmp::max(bet.timestamp, period_start);
+                let held_micros = now.micros().saturating_sub(held_since.micros());
+                stake = stake.saturating_add(bet.amount);
+                weight = weight.saturating_add(u128::from(bet.amount).saturating_mul(held_micros as u128));
+            }
+            
+            if stake > Amount::ZERO {
+                bettors.push(BettorSnapshot { owner, stake, weight });
+            }
+        }
         
         Ok(bettors)
     }
     
     pub async fn get_active_users_count(&self) -> Result<u64, ViewError> {
-        let mut count: u64 = 0;
-        self.user_bets
-            .for_each_index_value(|_owner, bets| {
-                if !bets.is_empty() {
-                    count += 1;
-                }
-                Ok(())
-            })
-            .await?;
-        Ok(count)
+        Ok(self.user_positions.count().await? as u64)
     }
     
     pub async fn get_app_supporters_count(&self, app_id: &str) -> Result<u32, ViewError> {
-        let mut count: u32 = 0;
-        self.user_bets
-            .for_each_index_value(|_owner, bets| {
-                if bets.iter().any(|bet| bet.app_id == app_id && bet.amount > Amount::ZERO) {
-                    count += 1;
-                }
-                Ok(())
-            })
-            .await?;
-        Ok(count)
+        Ok(self.app_supporters.get(app_id).await?.unwrap_or(0))
     }
 
+    /// Appends a lot to `owner`'s position on `app_id`.
+    ///
+    /// Lots placed before the last settlement and already unlocked weigh and redeem alike,
+    /// so they are merged into one; a position keeps at most one lot more than the bets
+    /// placed since then. The new lot unlocks under the current `bet_lock_micros`.
     pub async fn record_user_bet(&mut self, owner: &AccountOwner, app_id: &str, amount: Amount, timestamp: Timestamp) -> Result<(), ViewError> {
-        let mut bets = self.user_bets.get(owner).await?.unwrap_or_default();
+        let lots = self.get_user_app_bets(owner, app_id).await?;
+        let last_settle_time = *self.last_settle_time.get();
         
-        bets.push(UserBet {
+        let mut merged: Option<UserBet> = None;
+        let mut recent = Vec::new();
+        for lot in lots {
+            if lot.timestamp > last_settle_time || lot.unlock_at > timestamp {
+                recent.push(lot);
+                continue;
+            }
+            merged = Some(match merged {
+                None => lot,
+                Some(previous) => UserBet {
+                    app_id: lot.app_id,
+                    amount: previous.amount.saturating_add(lot.amount),
+                    timestamp: std::cmp::max(previous.timestamp, lot.timestamp),
+                    unlock_at: std::cmp::max(previous.unlock_at, lot.unlock_at),
+                },
+            });
+        }
+        let mut lots: Vec<UserBet> = merged.into_iter().chain(recent).collect();
+        
+        lots.push(UserBet {
             app_id: app_id.to_string(),
             amount,
             timestamp,
+            unlock_at: self.config.get().unlock_at(timestamp),
         });
         
-        self.user_bets.insert(owner, bets)?;
-        Ok(())
+        self.set_user_app_bets(owner, app_id, lots).await
     }
 
     pub async fn clear_user_bets_for_app(&mut self, owner: &AccountOwner, app_id: &str) -> Result<(), ViewError> {
-        let mut bets = self.user_bets.get(owner).await?.unwrap_or_default();
-        
-        bets.retain(|bet| bet.app_id != app_id);
-        
-        if bets.is_empty() {
-            self.user_bets.remove(owner)?;
-        } else {
-            self.user_bets.insert(owner, bets)?;
-        }
-        
-        Ok(())
+        self.set_user_app_bets(owner, app_id, Vec::new()).await
     }
 
     pub async fn update_user_points(&mut self, owner: &AccountOwner, points: Amount) -> Result<(), ViewError> {
@@ -367,31 +1109,130 @@ impl EdgeState {
         Ok(())
     }
 
-    pub async fn get_user_daily_earnings(&self, owner: &AccountOwner) -> Result<Amount, ViewError> {
-        Ok(self.user_daily_earnings.get(owner).await?.unwrap_or(Amount::ZERO))
+    pub async fn get_user_daily_earnings(&self, owner: &AccountOwner, current_time: Timestamp) -> Result<Amount, ViewError> {
+        self.get_live_earnings(owner, LeaderboardPeriod::Daily, current_time).await
     }
 
-    pub async fn get_user_weekly_earnings(&self, owner: &AccountOwner) -> Result<Amount, ViewError> {
-        Ok(self.user_weekly_earnings.get(owner).await?.unwrap_or(Amount::ZERO))
+    pub async fn get_user_weekly_earnings(&self, owner: &AccountOwner, current_time: Timestamp) -> Result<Amount, ViewError> {
+        self.get_live_earnings(owner, LeaderboardPeriod::Weekly, current_time).await
     }
 
-    pub async fn get_user_monthly_earnings(&self, owner: &AccountOwner) -> Result<Amount, ViewError> {
-        Ok(self.user_monthly_earnings.get(owner).await?.unwrap_or(Amount::ZERO))
+    pub async fn get_user_monthly_earnings(&self, owner: &AccountOwner, current_time: Timestamp) -> Result<Amount, ViewError> {
+        self.get_live_earnings(owner, LeaderboardPeriod::Monthly, current_time).await
     }
 
+    /// The user's earnings in the `period` containing `current_time`; an entry left from an
+    /// earlier period counts as zero, even before the keeper has closed that period.
+    async fn get_live_earnings(&self, owner: &AccountOwner, period: LeaderboardPeriod, current_time: Timestamp) -> Result<Amount, ViewError> {
+        let live_index = period.index_at(current_time);
+        let (earnings_map, _) = self.period_views(period);
+        Ok(match earnings_map.get(owner).await? {
+            Some(earnings) if earnings.index == live_index => earnings.amount,
+            _ => Amount::ZERO,
+        })
+    }
+
+    fn period_views(&self, period: LeaderboardPeriod) -> (&MapView<AccountOwner, PeriodEarnings>, &ByteMapView<(AccountOwner, Amount)>) {
+        match period {
+            LeaderboardPeriod::Daily => (&self.user_daily_earnings, &self.daily_leaderboard),
+            LeaderboardPeriod::Weekly => (&self.user_weekly_earnings, &self.weekly_leaderboard),
+            LeaderboardPeriod::Monthly => (&self.user_monthly_earnings, &self.monthly_leaderboard),
+        }
+    }
+
+    fn period_views_mut(&mut self, period: LeaderboardPeriod) -> (&mut MapView<AccountOwner, PeriodEarnings>, &mut ByteMapView<(AccountOwner, Amount)>) {
+        match period {
+            LeaderboardPeriod::Daily => (&mut self.user_daily_earnings, &mut self.daily_leaderboard),
+            LeaderboardPeriod::Weekly => (&mut self.user_weekly_earnings, &mut self.weekly_leaderboard),
+            LeaderboardPeriod::Monthly => (&mut self.user_monthly_earnings, &mut self.monthly_leaderboard),
+        }
+    }
+
+    /// Adds `amount` to the user's earnings in every live period and updates each leaderboard.
+    /// An entry left from a closed period is first moved to `earnings_archive`.
     pub async fn update_user_earnings(&mut self, owner: &AccountOwner, amount: Amount) -> Result<(), ViewError> {
-        let daily_earnings = self.get_user_daily_earnings(owner).await?;
-        self.user_daily_earnings.insert(owner, daily_earnings.saturating_add(amount))?;
-        
-        let weekly_earnings = self.get_user_weekly_earnings(owner).await?;
-        self.user_weekly_earnings.insert(owner, weekly_earnings.saturating_add(amount))?;
-        
-        let monthly_earnings = self.get_user_monthly_earnings(owner).await?;
-        self.user_monthly_earnings.insert(owner, monthly_earnings.saturating_add(amount))?;
-        
+        for period in LeaderboardPeriod::ALL {
+            let live_index = self.get_live_period_index(period).await?;
+            let (earnings_map, _) = self.period_views(period);
+            let previous = earnings_map.get(owner).await?;
+            
+            let mut earnings = amount;
+            if let Some(previous) = previous {
+                if previous.index == live_index {
+                    earnings = previous.amount.saturating_add(amount);
+                } else {
+                    self.earnings_archive.insert(&(period, previous.index, *owner), previous.amount)?;
+                }
+            }
+            
+            let (earnings_map, leaderboard) = self.period_views_mut(period);
+            if let Some(previous) = previous {
+                leaderboard.remove(leaderboard_key(previous.index, owner, previous.amount));
+            }
+            leaderboard.insert(leaderboard_key(live_index, owner, earnings), (owner.clone(), earnings));
+            earnings_map.insert(owner, PeriodEarnings { index: live_index, amount: earnings })?;
+        }
         Ok(())
     }
 
+    /// The `limit` best earners of the `period` containing `current_time`, at most
+    /// `LEADERBOARD_SIZE`, read from its leaderboard index.
+    pub async fn get_leaderboard(&self, period: LeaderboardPeriod, limit: usize, current_time: Timestamp) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
+        let live_index = period.index_at(current_time);
+        self.get_period_leaders(period, live_index, std::cmp::min(limit, LEADERBOARD_SIZE)).await
+    }
+
+    /// The `limit` best earners of the `period` with `index` still in its leaderboard index.
+    async fn get_period_leaders(&self, period: LeaderboardPeriod, index: u64, limit: usize) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
+        let mut leaders = Vec::new();
+        if limit == 0 {
+            return Ok(leaders);
+        }
+        // Later periods sort first; skip their entries.
+        let prefix = leaderboard_prefix(index);
+        let (_, leaderboard) = self.period_views(period);
+        leaderboard
+            .for_each_key_value_while(|key, entry| {
+                if key[..prefix.len()] < prefix[..] {
+                    return Ok(true);
+                }
+                if key[..prefix.len()] > prefix[..] {
+                    return Ok(false);
+                }
+                leaders.push(entry.into_owned());
+                Ok(leaders.len() < limit)
+            })
+            .await?;
+        Ok(leaders)
+    }
+
+    /// One-based position of `owner` on the leaderboard of the `period` containing
+    /// `current_time`, or `None` without earnings in it. Walks the entries ranked above the
+    /// user, so it costs O(rank).
+    pub async fn get_user_rank(&self, owner: &AccountOwner, period: LeaderboardPeriod, current_time: Timestamp) -> Result<Option<u32>, ViewError> {
+        let live_index = period.index_at(current_time);
+        let (earnings_map, leaderboard) = self.period_views(period);
+        let earnings = match earnings_map.get(owner).await? {
+            Some(earnings) if earnings.index == live_index => earnings.amount,
+            _ => return Ok(None),
+        };
+        let user_key = leaderboard_key(live_index, owner, earnings);
+        let prefix = leaderboard_prefix(live_index);
+        let mut ahead: u32 = 0;
+        leaderboard
+            .for_each_key_while(|key| {
+                if key >= user_key.as_slice() {
+                    return Ok(false);
+                }
+                if key[..prefix.len()] == prefix[..] {
+                    ahead += 1;
+                }
+                Ok(true)
+            })
+            .await?;
+        Ok(Some(ahead + 1))
+    }
+
     pub async fn get_last_daily_reset(&self) -> Result<Timestamp, ViewError> {
         Ok(self.last_daily_reset.get().clone())
     }
@@ -419,109 +1260,213 @@ impl EdgeState {
         Ok(())
     }
 
+    /// Closes every period that ended since its last reset: archives its leaderboard, then
+    /// moves the live period on. User entries are left in place and read as zero from then
+    /// on, so a reset costs the same however many users earned. Periods follow UTC days,
+    /// ISO weeks and calendar months.
     pub async fn check_and_perform_resets(&mut self, current_time: Timestamp) -> Result<(), ViewError> {
-        let last_daily = self.get_last_daily_reset().await?;
-        let twenty_four_hours = 24 * 60 * 60 * 1_000_000;
-        if current_time.micros() - last_daily.micros() >= twenty_four_hours {
-            self.reset_daily_earnings().await?;
-            self.update_last_daily_reset(current_time).await?;
-        }
-        
-        let last_weekly = self.get_last_weekly_reset().await?;
-        let seven_days = 7 * 24 * 60 * 60 * 1_000_000;
-        if current_time.micros() - last_weekly.micros() >= seven_days {
-            self.reset_weekly_earnings().await?;
-            self.update_last_weekly_reset(current_time).await?;
-        }
-        
-        let last_monthly = self.get_last_monthly_reset().await?;
-        let thirty_days = 30 * 24 * 60 * 60 * 1_000_000;
-        if current_time.micros() - last_monthly.micros() >= thirty_days {
-            self.reset_monthly_earnings().await?;
-            self.update_last_monthly_reset(current_time).await?;
+        for period in LeaderboardPeriod::ALL {
+            let closing_index = self.get_live_period_index(period).await?;
+            if period.index_at(current_time) == closing_index {
+                continue;
+            }
+            
+            self.archive_leaderboard(period, closing_index).await?;
+            match period {
+                LeaderboardPeriod::Daily => self.update_last_daily_reset(current_time).await?,
+                LeaderboardPeriod::Weekly => self.update_last_weekly_reset(current_time).await?,
+                LeaderboardPeriod::Monthly => self.update_last_monthly_reset(current_time).await?,
+            }
         }
         
         Ok(())
     }
 
-    pub async fn reset_daily_earnings(&mut self) -> Result<(), ViewError> {
-        let mut users = Vec::new();
-        self.user_daily_earnings.for_each_index(|user| {
-            users.push(user.clone());
-            Ok(())
-        }).await?;
-        
-        for user in users {
-            self.user_daily_earnings.remove(&user)?;
+    /// Index of the `period` the earnings maps currently count, which lags behind the clock
+    /// until the next reset. Only writes use it; reads go by `LeaderboardPeriod::index_at`.
+    pub async fn get_live_period_index(&self, period: LeaderboardPeriod) -> Result<u64, ViewError> {
+        let last_reset = match period {
+            LeaderboardPeriod::Daily => self.get_last_daily_reset().await?,
+            LeaderboardPeriod::Weekly => self.get_last_weekly_reset().await?,
+            LeaderboardPeriod::Monthly => self.get_last_monthly_reset().await?,
+        };
+        Ok(period.index_at(last_reset))
+    }
+
+    /// Archives the `LEADERBOARD_SIZE` best earners of the `period` with `index`, which costs
+    /// O(`LEADERBOARD_SIZE`) however many users earned. Index entries of the period go when
+    /// their users next earn.
+    async fn archive_leaderboard(&mut self, period: LeaderboardPeriod, index: u64) -> Result<(), ViewError> {
+        let leaders = self.get_period_leaders(period, index, LEADERBOARD_SIZE).await?;
+        if leaders.is_empty() {
+            return Ok(());
         }
         
+        self.leaderboard_archive.insert(&(period, index), final_leaderboard(period, index, leaders))?;
         Ok(())
     }
 
-    pub async fn reset_weekly_earnings(&mut self) -> Result<(), ViewError> {
-        let mut users = Vec::new();
-        self.user_weekly_earnings.for_each_index(|user| {
-            users.push(user.clone());
-            Ok(())
-        }).await?;
-        
-        for user in users {
-            self.user_weekly_earnings.remove(&user)?;
+    /// Final leaderboard of the `period` with `index`, or `None` while that period is still
+    /// running at `current_time` or if nobody earned in it. A period that ended but has not
+    /// been archived yet, because no settlement ran since, is read from the leaderboard index.
+    pub async fn get_archived_leaderboard(&self, period: LeaderboardPeriod, index: u64, current_time: Timestamp) -> Result<Option<ArchivedLeaderboard>, ViewError> {
+        if index >= period.index_at(current_time) {
+            return Ok(None);
         }
-        
+        if let Some(leaderboard) = self.leaderboard_archive.get(&(period, index)).await? {
+            return Ok(Some(leaderboard));
+        }
+        let leaders = self.get_period_leaders(period, index, LEADERBOARD_SIZE).await?;
+        if leaders.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(final_leaderboard(period, index, leaders)))
+    }
+
+    /// The user's earnings in the `period` with `index`: from the earnings map while its entry
+    /// is still tagged with that period, from the archive once a later period replaced it.
+    pub async fn get_user_period_earnings(&self, owner: &AccountOwner, period: LeaderboardPeriod, index: u64) -> Result<Amount, ViewError> {
+        let (earnings_map, _) = self.period_views(period);
+        if let Some(earnings) = earnings_map.get(owner).await? {
+            if earnings.index == index {
+                return Ok(earnings.amount);
+            }
+        }
+        Ok(self.earnings_archive.get(&(period, index, *owner)).await?.unwrap_or(Amount::ZERO))
+    }
+
+    pub async fn get_daily_leaderboard(&self, limit: usize, current_time: Timestamp) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
+        self.get_leaderboard(LeaderboardPeriod::Daily, limit, current_time).await
+    }
+
+    pub async fn get_weekly_leaderboard(&self, limit: usize, current_time: Timestamp) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
+        self.get_leaderboard(LeaderboardPeriod::Weekly, limit, current_time).await
+    }
+
+    pub async fn get_monthly_leaderboard(&self, limit: usize, current_time: Timestamp) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
+        self.get_leaderboard(LeaderboardPeriod::Monthly, limit, current_time).await
+    }
+
+    /// Logs `event`; a `Settled` event must follow the `record_settlement` of its receipt.
+    pub async fn record_event(&mut self, event: EdgeEvent) -> Result<(), ViewError> {
+        let logged = match event {
+            EdgeEvent::Registered(event) => LoggedEvent::Registered(event),
+            EdgeEvent::BetPlaced(event) => LoggedEvent::BetPlaced(event),
+            EdgeEvent::Redeemed(event) => LoggedEvent::Redeemed(event),
+            EdgeEvent::Settled(_) => LoggedEvent::Settled { epoch: self.next_settlement_epoch().await?.saturating_sub(1) },
+            EdgeEvent::AppAdded(event) => LoggedEvent::AppAdded(event),
+            EdgeEvent::AppRemoved(event) => LoggedEvent::AppRemoved(event),
+            EdgeEvent::PoolInjected(event) => LoggedEvent::PoolInjected(event),
+        };
+        self.events.push(logged);
         Ok(())
     }
 
-    pub async fn reset_monthly_earnings(&mut self) -> Result<(), ViewError> {
-        let mut users = Vec::new();
-        self.user_monthly_earnings.for_each_index(|user| {
-            users.push(user.clone());
-            Ok(())
-        }).await?;
-        
-        for user in users {
-            self.user_monthly_earnings.remove(&user)?;
+    pub async fn get_event_count(&self) -> Result<u64, ViewError> {
+        Ok(self.events.count() as u64)
+    }
+
+    /// Log entries with index in `from_index..`, capped at `limit` entries.
+    pub async fn get_logged_events(&self, from_index: u64, limit: usize) -> Result<Vec<LoggedEvent>, ViewError> {
+        let count = self.events.count();
+        let start = usize::try_from(from_index).map_or(count, |from_index| std::cmp::min(from_index, count));
+        let end = std::cmp::min(start.saturating_add(limit), count);
+        self.events.read(start..end).await
+    }
+
+    /// Events with index in `from_index..`, capped at `limit` entries.
+    pub async fn get_events(&self, from_index: u64, limit: usize) -> Result<Vec<EdgeEvent>, ViewError> {
+        let mut events = Vec::new();
+        for logged in self.get_logged_events(from_index, limit).await? {
+            events.push(match logged {
+                LoggedEvent::Registered(event) => EdgeEvent::Registered(event),
+                LoggedEvent::BetPlaced(event) => EdgeEvent::BetPlaced(event),
+                LoggedEvent::Redeemed(event) => EdgeEvent::Redeemed(event),
+                LoggedEvent::Settled { epoch } => {
+                    let record = self.get_settlement(epoch).await?
+                        .ok_or_else(|| ViewError::NotFound(format!("settlement receipt {epoch}")))?;
+                    EdgeEvent::Settled(record.settled_event())
+                }
+                LoggedEvent::AppAdded(event) => EdgeEvent::AppAdded(event),
+                LoggedEvent::AppRemoved(event) => EdgeEvent::AppRemoved(event),
+                LoggedEvent::PoolInjected(event) => EdgeEvent::PoolInjected(event),
+            });
         }
-        
+        Ok(events)
+    }
+
+    pub async fn next_settlement_epoch(&self) -> Result<u64, ViewError> {
+        Ok(self.settlements.count() as u64)
+    }
+
+    pub async fn record_settlement(&mut self, record: SettlementRecord) -> Result<(), ViewError> {
+        self.settlements.push(record);
         Ok(())
     }
 
-    pub async fn get_daily_leaderboard(&self, limit: usize) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
-        let mut leaderboard = Vec::new();
-        
-        self.user_daily_earnings.for_each_index_value(|user, earnings| {
-            leaderboard.push((user.clone(), *earnings));
-            Ok(())
-        }).await?;
-        
-        leaderboard.sort_by(|a, b| b.1.cmp(&a.1));
-        
-        Ok(leaderboard.into_iter().take(limit).collect())
+    pub async fn get_settlement(&self, epoch: u64) -> Result<Option<SettlementRecord>, ViewError> {
+        match usize::try_from(epoch) {
+            Ok(index) => self.settlements.get(index).await,
+            Err(_) => Ok(None),
+        }
     }
 
-    pub async fn get_weekly_leaderboard(&self, limit: usize) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
-        let mut leaderboard = Vec::new();
-        
-        self.user_weekly_earnings.for_each_index_value(|user, earnings| {
-            leaderboard.push((user.clone(), *earnings));
-            Ok(())
-        }).await?;
-        
-        leaderboard.sort_by(|a, b| b.1.cmp(&a.1));
-        
-        Ok(leaderboard.into_iter().take(limit).collect())
+    /// Settlement records for epochs `from_epoch..=to_epoch`, at most `limit` of them,
+    /// clamped to the recorded range.
+    pub async fn get_settlements(&self, from_epoch: u64, to_epoch: u64, limit: usize) -> Result<Vec<SettlementRecord>, ViewError> {
+        let count = self.settlements.count();
+        let start = match usize::try_from(from_epoch) {
+            Ok(start) => start,
+            Err(_) => return Ok(Vec::new()),
+        };
+        let end = usize::try_from(to_epoch).map_or(count, |to_epoch| to_epoch.saturating_add(1));
+        let end = std::cmp::min(std::cmp::min(end, count), start.saturating_add(limit));
+        if start >= end {
+            return Ok(Vec::new());
+        }
+        self.settlements.read(start..end).await
     }
+}
 
-    pub async fn get_monthly_leaderboard(&self, limit: usize) -> Result<Vec<(AccountOwner, Amount)>, ViewError> {
-        let mut leaderboard = Vec::new();
-        
-        self.user_monthly_earnings.for_each_index_value(|user, earnings| {
-            leaderboard.push((user.clone(), *earnings));
-            Ok(())
-        }).await?;
-        
-        leaderboard.sort_by(|a, b| b.1.cmp(&a.1));
-        
-        Ok(leaderboard.into_iter().take(limit).collect())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranking_keys_sort_by_descending_total_then_app_id() {
+        let mut keys = vec![
+            ranking_key("b", Amount::from_tokens(5)),
+            ranking_key("a", Amount::ZERO),
+            ranking_key("c", Amount::from_tokens(5)),
+            ranking_key("d", Amount::from_attos(u128::MAX)),
+            ranking_key("a", Amount::from_tokens(7)),
+        ];
+        keys.sort();
+        assert_eq!(keys, vec![
+            ranking_key("d", Amount::from_attos(u128::MAX)),
+            ranking_key("a", Amount::from_tokens(7)),
+            ranking_key("b", Amount::from_tokens(5)),
+            ranking_key("c", Amount::from_tokens(5)),
+            ranking_key("a", Amount::ZERO),
+        ]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn leaderboard_keys_sort_later_periods_first_then_by_descending_earnings() {
+        let (alice, bob) = (AccountOwner::Address20([1; 20]), AccountOwner::Address20([2; 20]));
+        let mut keys = vec![
+            leaderboard_key(3, &alice, Amount::from_tokens(9)),
+            leaderboard_key(4, &bob, Amount::from_tokens(2)),
+            leaderboard_key(4, &alice, Amount::from_tokens(2)),
+            leaderboard_key(4, &bob, Amount::from_tokens(8)),
+        ];
+        keys.sort();
+        assert_eq!(keys, vec![
+            leaderboard_key(4, &bob, Amount::from_tokens(8)),
+            leaderboard_key(4, &alice, Amount::from_tokens(2)),
+            leaderboard_key(4, &bob, Amount::from_tokens(2)),
+            leaderboard_key(3, &alice, Amount::from_tokens(9)),
+        ]);
+        assert!(keys[..3].iter().all(|key| key.starts_with(&leaderboard_prefix(4))));
+    }
+}
@@ -1,21 +1,361 @@
-use linera_sdk::linera_base_types::{AccountOwner, Timestamp, Amount};
+use linera_sdk::linera_base_types::{Amount, TimeDelta, Timestamp};
 use serde::{Deserialize, Serialize};
+use crate::reward::RewardStrategyKind;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct UserBet {
-    pub app_id: String,
-    pub amount: Amount,
+const MICROS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000;
+const LOW_MASK: u128 = u64::MAX as u128;
+
+/// Computes `value * numerator / denominator` rounded down, without overflowing the
+/// intermediate product. Returns zero when `denominator` is zero.
+pub fn mul_div(value: u128, numerator: u128, denominator: u128) -> u128 {
+    if denominator == 0 {
+        return 0;
+    }
+    if let Some(product) = value.checked_mul(numerator) {
+        return product / denominator;
+    }
+
+    let (value_high, value_low) = (value >> 64, value & LOW_MASK);
+    let (numerator_high, numerator_low) = (numerator >> 64, numerator & LOW_MASK);
+    let low_low = value_low * numerator_low;
+    let high_low = value_high * numerator_low;
+    let low_high = value_low * numerator_high;
+    let high_high = value_high * numerator_high;
+    let cross = (low_low >> 64) + (high_low & LOW_MASK) + (low_high & LOW_MASK);
+    let product_low = (cross << 64) | (low_low & LOW_MASK);
+    let product_high = high_high + (high_low >> 64) + (low_high >> 64) + (cross >> 64);
+
+    let mut remainder: u128 = 0;
+    let mut quotient: u128 = 0;
+    for bit in (0..256).rev() {
+        let next_bit = if bit >= 128 {
+            (product_high >> (bit - 128)) & 1
+        } else {
+            (product_low >> bit) & 1
+        };
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | next_bit;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            if bit >= 128 {
+                return u128::MAX;
+            }
+            quotient |= 1 << bit;
+        }
+    }
+    quotient
+}
+
+/// Administrative roles. The owner implicitly holds every role.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum Role {
+    /// Adds and removes applications.
+    Curator,
+    /// Triggers settlement.
+    Keeper,
+    /// Injects points into the pool.
+    Treasurer,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::Curator, Role::Keeper, Role::Treasurer];
+}
+
+/// Earnings periods with their own leaderboard.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum LeaderboardPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [LeaderboardPeriod; 3] = [LeaderboardPeriod::Daily, LeaderboardPeriod::Weekly, LeaderboardPeriod::Monthly];
+
+    /// Index of the UTC period containing `timestamp`: days since the Unix epoch, ISO
+    /// weeks (starting on Monday) since the week of the epoch, or `year * 12 + month - 1`.
+    pub fn index_at(self, timestamp: Timestamp) -> u64 {
+        let days = timestamp.micros() / MICROS_PER_DAY;
+        match self {
+            LeaderboardPeriod::Daily => days,
+            // 1970-01-01 was a Thursday, three days after the Monday starting its week.
+            LeaderboardPeriod::Weekly => (days + 3) / 7,
+            LeaderboardPeriod::Monthly => {
+                let (year, month) = civil_from_days(days as i64);
+                (year * 12 + month as i64 - 1) as u64
+            }
+        }
+    }
+
+    /// Start of the period with `index`, clamped to the Unix epoch.
+    pub fn start_of(self, index: u64) -> Timestamp {
+        let days = match self {
+            LeaderboardPeriod::Daily => index as i64,
+            LeaderboardPeriod::Weekly => index as i64 * 7 - 3,
+            LeaderboardPeriod::Monthly => days_from_civil((index / 12) as i64, (index % 12) as u32 + 1),
+        };
+        Timestamp::from((days.max(0) as u64).saturating_mul(MICROS_PER_DAY))
+    }
+}
+
+/// Year and month (1-12) of the day `days` after 1970-01-01, in the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month as u32)
+}
+
+/// Days from 1970-01-01 to the first day of `month` (1-12) of `year`.
+fn days_from_civil(year: i64, month: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let shifted_month = (month as i64 + 9) % 12;
+    let day_of_year = (153 * shifted_month + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Economic rules of a deployment, fixed at instantiation and stored in `EdgeState::config`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, async_graphql::SimpleObject, async_graphql::InputObject)]
+#[graphql(input_name = "EdgeConfigInput")]
+#[serde(default)]
+pub struct EdgeConfig {
+    /// Points seeded into the pool when the application is created.
+    pub initial_pool: Amount,
+    /// Points each user claims once with `EdgeOperation::Register`.
+    pub initial_balance: Amount,
+    /// Maximum a single user may have at stake on one application.
+    pub max_bet_per_app: Amount,
+    /// Smallest amount a single bet may place.
+    pub min_bet: Amount,
+    /// Redeem fee in percent of the redeemed amount; the fee goes to the pool.
+    pub redeem_fee_percent: u64,
+    /// Lower bound of the redeem fee.
+    pub min_redeem_fee: Amount,
+    /// Time a bet stays locked after it is placed.
+    pub bet_lock_micros: u64,
+    /// Extra percent of a still-locked amount paid to the pool on early redemption;
+    /// zero forbids redeeming locked bets.
+    pub early_exit_penalty_percent: u64,
+    /// Minimum time between two settlements.
+    pub settle_interval_micros: u64,
+    /// Percent of the top apps' total bets distributed per settlement.
+    pub distribution_percent: u64,
+    /// Curve used to split the distribution between ranked apps.
+    pub reward_strategy: RewardStrategyKind,
+    /// Share of the distribution per rank, in percent, for `FixedWeights`; its length is
+    /// the number of ranked apps.
+    pub reward_weights: Vec<u32>,
+    /// Number of ranked apps for every strategy other than `FixedWeights`.
+    pub ranked_apps: u32,
+    /// Share of each rank relative to the rank above it, for `ExponentialDecay`.
+    pub decay_percent: u64,
+    /// Share of the top app, for `WinnerTakeMost`; between 50 and 100.
+    pub winner_percent: u64,
+    /// Bonus percent added per supporter of an app.
+    pub supporter_bonus_percent: u64,
+    /// Cap on the total supporter bonus.
+    pub max_supporter_bonus_percent: u64,
+    /// First rank (zero-based) eligible for the growth bonus.
+    pub growth_bonus_from_rank: u32,
+    /// Growth bonus percent per rank below the last ranked position.
+    pub growth_bonus_percent_per_rank: u64,
+    /// Bonus percent for apps added within `new_app_window_micros`.
+    pub new_app_bonus_percent: u64,
+    pub new_app_window_micros: u64,
+    /// Delay between an `UpdateConfig` operation and the new config taking effect.
+    pub config_update_delay_micros: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct AppRanking {
-    pub app_id: String,
-    pub total_bet: Amount,
-    pub rank: u32,
+/// A config update waiting for its timelock to expire.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, async_graphql::SimpleObject)]
+pub struct PendingConfig {
+    pub config: EdgeConfig,
+    pub effective_at: Timestamp,
 }
 
-pub const REWARD_WEIGHTS: [u32; 10] = [25, 18, 14, 10, 8, 7, 6, 5, 4, 3];
-pub const INITIAL_BALANCE: u64 = 100;
-pub const MAX_BET_PER_APP: u64 = 100;
-pub const REDEEM_FEE_PERCENT: u64 = 1;
-pub const POOL_DISTRIBUTION_PERCENT: u64 = 80;
\ No newline at end of file
+impl Default for EdgeConfig {
+    fn default() -> Self {
+        Self {
+            initial_pool: Amount::from_tokens(10_000),
+            initial_balance: Amount::from_tokens(50),
+            max_bet_per_app: Amount::from_tokens(100),
+            min_bet: Amount::from_tokens(1),
+            redeem_fee_percent: 1,
+            min_redeem_fee: Amount::from_tokens(1),
+            bet_lock_micros: 60_000_000,
+            early_exit_penalty_percent: 0,
+            settle_interval_micros: 60_000_000,
+            distribution_percent: 1,
+            reward_strategy: RewardStrategyKind::FixedWeights,
+            reward_weights: vec![15, 14, 13, 12, 11, 10, 9, 8, 7, 6],
+            ranked_apps: 10,
+            decay_percent: 80,
+            winner_percent: 50,
+            supporter_bonus_percent: 1,
+            max_supporter_bonus_percent: 10,
+            growth_bonus_from_rank: 5,
+            growth_bonus_percent_per_rank: 1,
+            new_app_bonus_percent: 5,
+            new_app_window_micros: 7 * MICROS_PER_DAY,
+            config_update_delay_micros: 60 * 60 * 1_000_000,
+        }
+    }
+}
+
+impl EdgeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.reward_strategy == RewardStrategyKind::FixedWeights && self.reward_weights.is_empty() {
+            return Err("reward_weights must not be empty".to_string());
+        }
+        if self.reward_strategy != RewardStrategyKind::FixedWeights && self.ranked_apps == 0 {
+            return Err("ranked_apps must be greater than zero".to_string());
+        }
+        // Only single weights are bounded: the weights may sum to more than 100 (the
+        // defaults sum to 105), in which case the ranked apps share more than
+        // `distribution_percent` of their total bets.
+        if self.reward_weights.iter().any(|weight| *weight > 100) {
+            return Err("reward_weights must not exceed 100".to_string());
+        }
+        if self.redeem_fee_percent > 100 || self.distribution_percent > 100 || self.early_exit_penalty_percent > 100
+            || self.decay_percent > 100 || self.winner_percent > 100
+        {
+            return Err("percentages must not exceed 100".to_string());
+        }
+        // With a single runner-up it gets `100 - winner_percent`, so a smaller winner share
+        // would pay the top app less than the one behind it.
+        if self.winner_percent < 50 {
+            return Err("winner_percent must be at least 50".to_string());
+        }
+        if self.max_bet_per_app == Amount::ZERO {
+            return Err("max_bet_per_app must be greater than zero".to_string());
+        }
+        if self.min_bet == Amount::ZERO || self.min_bet > self.max_bet_per_app {
+            return Err("min_bet must be greater than zero and at most max_bet_per_app".to_string());
+        }
+        Ok(())
+    }
+
+    /// Fee charged for redeeming `amount`.
+    pub fn redeem_fee(&self, amount: Amount) -> Amount {
+        let fee = Amount::from_attos(mul_div(u128::from(amount), self.redeem_fee_percent as u128, 100));
+        std::cmp::max(fee, self.min_redeem_fee)
+    }
+
+    /// When a bet placed at `placed_at` can be redeemed without penalty.
+    pub fn unlock_at(&self, placed_at: Timestamp) -> Timestamp {
+        placed_at.saturating_add(TimeDelta::from_micros(self.bet_lock_micros))
+    }
+
+    /// Bonus percent applied on top of the base reward of the app at zero-based `rank`.
+    pub fn bonus_percent(&self, rank: usize, supporters: u32, app_age_micros: u64) -> u64 {
+        let supporter_bonus = std::cmp::min(
+            (supporters as u64).saturating_mul(self.supporter_bonus_percent),
+            self.max_supporter_bonus_percent,
+        );
+
+        let growth_bonus = if rank >= self.growth_bonus_from_rank as usize {
+            (self.reward_weights.len().saturating_sub(rank) as u64).saturating_mul(self.growth_bonus_percent_per_rank)
+        } else {
+            0
+        };
+
+        let new_app_bonus = if app_age_micros < self.new_app_window_micros {
+            self.new_app_bonus_percent
+        } else {
+            0
+        };
+
+        supporter_bonus + growth_bonus + new_app_bonus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Timestamp `micros` into the day `days` after 1970-01-01.
+    fn at(days: u64, micros: u64) -> Timestamp {
+        Timestamp::from(days * MICROS_PER_DAY + micros)
+    }
+
+    const LAST_MICRO: u64 = MICROS_PER_DAY - 1;
+
+    #[test]
+    fn months_and_years_roll_over_at_utc_midnight() {
+        let monthly = LeaderboardPeriod::Monthly;
+        // 2023-12-31 and 2024-01-01.
+        assert_eq!(monthly.index_at(at(19_722, LAST_MICRO)), 2023 * 12 + 11);
+        assert_eq!(monthly.index_at(at(19_723, 0)), 2024 * 12);
+        assert_eq!(monthly.start_of(2024 * 12), at(19_723, 0));
+        assert_eq!(LeaderboardPeriod::Daily.index_at(at(19_723, 0)), 19_723);
+        assert_eq!(LeaderboardPeriod::Daily.start_of(19_723), at(19_723, 0));
+    }
+
+    #[test]
+    fn leap_days_follow_the_gregorian_rules() {
+        let monthly = LeaderboardPeriod::Monthly;
+        // 2024-02-29 is still February, 2024-03-01 is March.
+        assert_eq!(monthly.index_at(at(19_782, LAST_MICRO)), 2024 * 12 + 1);
+        assert_eq!(monthly.index_at(at(19_783, 0)), 2024 * 12 + 2);
+        assert_eq!(monthly.start_of(2024 * 12 + 2), at(19_783, 0));
+        // 2000 is a leap year: 2000-02-29.
+        assert_eq!(monthly.index_at(at(11_016, 0)), 2000 * 12 + 1);
+        // 2100 is not: 2100-02-28 is followed by 2100-03-01.
+        assert_eq!(monthly.index_at(at(47_540, LAST_MICRO)), 2100 * 12 + 1);
+        assert_eq!(monthly.index_at(at(47_541, 0)), 2100 * 12 + 2);
+        assert_eq!(monthly.start_of(2100 * 12 + 2), at(47_541, 0));
+
+        assert_eq!(days_from_civil(2024, 3) - days_from_civil(2024, 2), 29);
+        assert_eq!(days_from_civil(2100, 3) - days_from_civil(2100, 2), 28);
+        assert_eq!(days_from_civil(2000, 3) - days_from_civil(2000, 2), 29);
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        let weekly = LeaderboardPeriod::Weekly;
+        // Sunday 2023-12-31 and Monday 2024-01-01.
+        let sunday = weekly.index_at(at(19_722, LAST_MICRO));
+        let monday = weekly.index_at(at(19_723, 0));
+        assert_eq!(monday, sunday + 1);
+        assert_eq!(weekly.start_of(monday), at(19_723, 0));
+        assert_eq!(weekly.index_at(at(19_729, LAST_MICRO)), monday);
+        // The week of the epoch began on Monday 1969-12-29, so its start is clamped.
+        assert_eq!(weekly.index_at(at(0, 0)), 0);
+        assert_eq!(weekly.index_at(at(4, 0)), 1);
+        assert_eq!(weekly.start_of(0), at(0, 0));
+        assert_eq!(weekly.start_of(1), at(4, 0));
+    }
+
+    #[test]
+    fn civil_conversions_round_trip() {
+        for year in 1970..2500 {
+            for month in 1..=12 {
+                let days = days_from_civil(year, month);
+                assert_eq!(civil_from_days(days), (year, month));
+                assert_eq!(civil_from_days(days - 1).1, if month == 1 { 12 } else { month - 1 });
+            }
+        }
+    }
+
+    #[test]
+    fn every_timestamp_lies_in_the_period_it_is_indexed_to() {
+        for days in (0..(2200 - 1970) * 366).step_by(3) {
+            for micros in [0, MICROS_PER_DAY / 2, LAST_MICRO] {
+                let timestamp = at(days, micros);
+                for period in LeaderboardPeriod::ALL {
+                    let index = period.index_at(timestamp);
+                    assert!(period.start_of(index) <= timestamp, "{period:?} {days}");
+                    assert!(timestamp < period.start_of(index + 1), "{period:?} {days}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Property tests for the runtime-free settlement engine.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use linera_edge::reward::RewardStrategyKind;
+use linera_edge::settlement::{plan_settlement, AppSnapshot, BettorSnapshot, PayoutPlan, SettlementSnapshot};
+use linera_edge::types::{mul_div, EdgeConfig};
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use proptest::prelude::*;
+
+const MAX_STAKE: u128 = 1_000 * 1_000_000_000_000_000_000;
+const MAX_WEIGHT: u128 = MAX_STAKE * 3_600_000_000;
+
+fn owner(index: usize) -> AccountOwner {
+    AccountOwner::Address20([index as u8; 20])
+}
+
+/// Apps with `(stake, weight)` per bettor, ranked the way the contract ranks them.
+fn snapshot_strategy() -> impl Strategy<Value = SettlementSnapshot> {
+    let bettor = (1..=MAX_STAKE, 0..=MAX_WEIGHT);
+    let app = prop::collection::vec(bettor, 1..8);
+    (prop::collection::vec(app, 1..12), 0..=MAX_STAKE * 100, 0..30 * 24 * 3_600_000_000u64).prop_map(
+        |(apps, pool, age_micros)| {
+            let mut apps: Vec<AppSnapshot> = apps
+                .into_iter()
+                .enumerate()
+                .map(|(app_index, bettors)| {
+                    let bettors: Vec<BettorSnapshot> = bettors
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, (stake, weight))| BettorSnapshot {
+                            owner: owner(index),
+                            stake: Amount::from_attos(stake),
+                            weight,
+                        })
+                        .collect();
+                    let total_bet = bettors.iter().fold(Amount::ZERO, |acc, bettor| acc.saturating_add(bettor.stake));
+                    AppSnapshot { app_id: format!("app-{app_index}"), total_bet, age_micros, bettors }
+                })
+                .collect();
+            // Best first, as `EdgeState::get_top_apps` ranks them.
+            apps.sort_by(|a, b| b.total_bet.cmp(&a.total_bet).then_with(|| a.app_id.cmp(&b.app_id)));
+            SettlementSnapshot { apps, pool: Amount::from_attos(pool) }
+        },
+    )
+}
+
+fn strategy_kind() -> impl Strategy<Value = RewardStrategyKind> {
+    prop_oneof![
+        Just(RewardStrategyKind::FixedWeights),
+        Just(RewardStrategyKind::LinearDecay),
+        Just(RewardStrategyKind::ExponentialDecay),
+        Just(RewardStrategyKind::WinnerTakeMost),
+        Just(RewardStrategyKind::Quadratic),
+    ]
+}
+
+fn config_with(reward_strategy: RewardStrategyKind, distribution_percent: u64) -> EdgeConfig {
+    EdgeConfig { reward_strategy, distribution_percent, ..EdgeConfig::default() }
+}
+
+fn total_paid(plan: &PayoutPlan) -> u128 {
+    plan.payouts.iter().map(|payout| u128::from(payout.amount)).sum()
+}
+
+proptest! {
+    #[test]
+    fn payouts_never_exceed_the_pool(
+        snapshot in snapshot_strategy(),
+        kind in strategy_kind(),
+        distribution_percent in 0..=100u64,
+    ) {
+        let plan = plan_settlement(&config_with(kind, distribution_percent), &snapshot).unwrap();
+
+        let paid = total_paid(&plan);
+        prop_assert!(paid <= u128::from(snapshot.pool));
+        prop_assert_eq!(paid + u128::from(plan.pool_after), u128::from(snapshot.pool));
+
+        for app in &plan.apps {
+            let app_paid: u128 = plan.payouts.iter()
+                .filter(|payout| payout.app_id == app.app_id)
+                .map(|payout| u128::from(payout.amount))
+                .sum();
+            prop_assert_eq!(app_paid, u128::from(app.reward));
+        }
+    }
+
+    #[test]
+    fn base_rewards_do_not_increase_with_rank(
+        snapshot in snapshot_strategy(),
+        kind in prop_oneof![
+            Just(RewardStrategyKind::FixedWeights),
+            Just(RewardStrategyKind::LinearDecay),
+            Just(RewardStrategyKind::ExponentialDecay),
+            Just(RewardStrategyKind::WinnerTakeMost),
+        ],
+        decay_percent in 1..=100u64,
+        winner_percent in 50..=100u64,
+    ) {
+        let config = EdgeConfig {
+            decay_percent,
+            winner_percent,
+            supporter_bonus_percent: 0,
+            growth_bonus_percent_per_rank: 0,
+            new_app_bonus_percent: 0,
+            ..config_with(kind, 1)
+        };
+        let plan = plan_settlement(&config, &snapshot).unwrap();
+
+        for pair in plan.apps.windows(2) {
+            prop_assert!(pair[0].rank < pair[1].rank);
+            prop_assert!(pair[0].base_reward >= pair[1].base_reward);
+        }
+    }
+
+    #[test]
+    fn shares_within_an_app_are_pro_rata(
+        snapshot in snapshot_strategy(),
+        kind in strategy_kind(),
+    ) {
+        let plan = plan_settlement(&config_with(kind, 10), &snapshot).unwrap();
+
+        for app in &plan.apps {
+            let bettors = &snapshot.apps.iter().find(|snapshot| snapshot.app_id == app.app_id).unwrap().bettors;
+            let total_weight: u128 = bettors.iter().map(|bettor| bettor.weight).sum();
+            let paid_to = |owner: &AccountOwner| -> u128 {
+                plan.payouts.iter()
+                    .filter(|payout| payout.app_id == app.app_id && payout.owner == *owner)
+                    .map(|payout| u128::from(payout.amount))
+                    .sum()
+            };
+
+            for bettor in bettors {
+                // `app.reward` is the sum of rounded-down shares, so it is at most one atto
+                // per bettor below the reward that was split.
+                let lower = mul_div(u128::from(app.reward), bettor.weight, total_weight);
+                let paid = paid_to(&bettor.owner);
+                prop_assert!(paid >= lower);
+                prop_assert!(paid <= lower + bettors.len() as u128);
+            }
+            for first in bettors {
+                for second in bettors {
+                    if first.weight <= second.weight {
+                        prop_assert!(paid_to(&first.owner) <= paid_to(&second.owner));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! End-to-end tests deploying the contract and service on a `TestValidator`.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use linera_edge::types::{EdgeConfig, Role};
+use linera_edge::{EdgeAbi, EdgeOperation};
+use linera_sdk::linera_base_types::{AccountOwner, AccountSecretKey, Amount, ApplicationId, Ed25519SecretKey, TimeDelta};
+use linera_sdk::test::{ActiveChain, QueryOutcome, TestValidator};
+use serde_json::Value;
+
+struct Setup {
+    validator: TestValidator,
+    chain: ActiveChain,
+    application_id: ApplicationId<EdgeAbi>,
+    owner: AccountOwner,
+}
+
+async fn setup(config: EdgeConfig) -> Setup {
+    let (validator, module_id) = TestValidator::with_current_module::<EdgeAbi, (), EdgeConfig>().await;
+    let mut chain = validator.new_chain().await;
+    let owner = AccountOwner::from(chain.public_key());
+    let application_id = chain.create_application(module_id, (), config, vec![]).await;
+    let setup = Setup { validator, chain, application_id, owner };
+    setup.execute(EdgeOperation::Register).await;
+    setup
+}
+
+impl Setup {
+    async fn execute(&self, operation: EdgeOperation) {
+        self.chain
+            .add_block(|block| {
+                block.with_operation(self.application_id, operation);
+            })
+            .await;
+    }
+
+    async fn try_execute(&self, operation: EdgeOperation) -> bool {
+        self.chain
+            .try_add_block(|block| {
+                block.with_operation(self.application_id, operation);
+            })
+            .await
+            .is_ok()
+    }
+
+    async fn query(&self, query: &str) -> Value {
+        let QueryOutcome { response, .. } = self.chain.graphql_query(self.application_id, query).await;
+        response
+    }
+
+    async fn query_amount(&self, query: &str, field: &str) -> Amount {
+        serde_json::from_value(self.query(query).await[field].clone()).unwrap()
+    }
+
+    async fn balance(&self, owner: AccountOwner) -> Amount {
+        self.query_amount(&format!("query {{ getBalance(owner: \"{owner}\") }}"), "getBalance").await
+    }
+
+    async fn pool(&self) -> Amount {
+        self.query_amount("query { getPoolAmount }", "getPoolAmount").await
+    }
+
+    async fn add_app(&self, app_id: &str) {
+        self.execute(EdgeOperation::AddApplication {
+            app_id: app_id.to_string(),
+            name: format!("App {app_id}"),
+            description: String::new(),
+        })
+        .await;
+    }
+
+    async fn bet(&self, app_id: &str, tokens: u128) {
+        self.execute(EdgeOperation::Bet { app_id: app_id.to_string(), amount: Amount::from_tokens(tokens) }).await;
+    }
+
+    /// The same deployment, with blocks signed by `key_pair` instead of the owner's key.
+    fn signed_by(&self, key_pair: AccountSecretKey) -> Setup {
+        let owner = AccountOwner::from(key_pair.public());
+        let mut chain = self.chain.clone();
+        chain.set_key_pair(key_pair);
+        Setup { validator: self.validator.clone(), chain, application_id: self.application_id, owner }
+    }
+
+    fn advance(&self, seconds: u64) {
+        self.validator.clock().add(TimeDelta::from_secs(seconds));
+    }
+
+    async fn assert_supply_balanced(&self) {
+        let audit = self.query("query { getSupplyAudit { balanced } }").await;
+        assert_eq!(audit["getSupplyAudit"]["balanced"], Value::Bool(true));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn registration_mints_the_starting_grant_once() {
+    let setup = setup(EdgeConfig::default()).await;
+    let newcomer = AccountOwner::from(setup.validator.new_chain().await.public_key());
+    let account = |owner: AccountOwner| format!("query {{ getAccount(owner: \"{owner}\") {{ balance isRegistered }} }}");
+
+    // Reading an unregistered account neither shows nor mints a grant.
+    assert_eq!(setup.balance(newcomer).await, Amount::ZERO);
+    let unregistered = setup.query(&account(newcomer)).await;
+    assert_eq!(unregistered["getAccount"]["isRegistered"], Value::Bool(false));
+    setup.assert_supply_balanced().await;
+
+    let registered = setup.query(&account(setup.owner)).await;
+    assert_eq!(registered["getAccount"]["isRegistered"], Value::Bool(true));
+    assert_eq!(setup.balance(setup.owner).await, Amount::from_tokens(50));
+    assert!(!setup.try_execute(EdgeOperation::Register).await);
+    assert_eq!(setup.balance(setup.owner).await, Amount::from_tokens(50));
+    setup.assert_supply_balanced().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bet_and_redeem_update_positions_and_pool() {
+    let setup = setup(EdgeConfig::default()).await;
+    setup.add_app("1").await;
+
+    setup.bet("1", 10).await;
+    assert_eq!(setup.balance(setup.owner).await, Amount::from_tokens(40));
+    assert_eq!(
+        setup.query_amount("query { getAppTotalBet(appId: \"1\") }", "getAppTotalBet").await,
+        Amount::from_tokens(10)
+    );
+    let bets = setup.query(&format!("query {{ getUserBets(owner: \"{}\") {{ appId amount }} }}", setup.owner)).await;
+    assert_eq!(bets["getUserBets"].as_array().unwrap().len(), 1);
+
+    setup.advance(120);
+    setup.execute(EdgeOperation::Redeem { app_id: "1".to_string(), amount: Amount::from_tokens(10) }).await;
+
+    // The minimum fee of 1 point moves from the stake to the pool.
+    assert_eq!(setup.balance(setup.owner).await, Amount::from_tokens(49));
+    assert_eq!(setup.pool().await, Amount::from_tokens(10_001));
+    setup.assert_supply_balanced().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_bets_and_locked_redemptions_are_rejected() {
+    let setup = setup(EdgeConfig::default()).await;
+    setup.add_app("1").await;
+
+    let bet = |app_id: &str, tokens: u128| EdgeOperation::Bet { app_id: app_id.to_string(), amount: Amount::from_tokens(tokens) };
+    assert!(!setup.try_execute(bet("unknown", 10)).await);
+    assert!(!setup.try_execute(bet("1", 0)).await);
+    assert!(!setup.try_execute(bet("1", 51)).await);
+
+    setup.bet("1", 10).await;
+    let redeem = EdgeOperation::Redeem { app_id: "1".to_string(), amount: Amount::from_tokens(5) };
+    assert!(!setup.try_execute(redeem.clone()).await);
+
+    setup.advance(120);
+    assert!(setup.try_execute(redeem).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn raising_the_lock_leaves_placed_bets_unlocked() {
+    let setup = setup(EdgeConfig::default()).await;
+    setup.add_app("1").await;
+    setup.bet("1", 10).await;
+    let bets = format!("query {{ getUserBets(owner: \"{}\") {{ unlockAt }} }}", setup.owner);
+    let placed_unlock_at = setup.query(&bets).await["getUserBets"][0]["unlockAt"].clone();
+
+    let config = EdgeConfig { bet_lock_micros: 24 * 60 * 60 * 1_000_000, ..EdgeConfig::default() };
+    setup.execute(EdgeOperation::UpdateConfig { config }).await;
+    setup.advance(60 * 60 + 1);
+    setup.bet("1", 5).await;
+
+    // The first lot keeps the one-minute lock it was placed under; only the new one is
+    // locked for a day.
+    assert_eq!(setup.query(&bets).await["getUserBets"][0]["unlockAt"], placed_unlock_at);
+    let redeem = |tokens: u128| EdgeOperation::Redeem { app_id: "1".to_string(), amount: Amount::from_tokens(tokens) };
+    assert!(!setup.try_execute(redeem(15)).await);
+    assert!(setup.try_execute(redeem(10)).await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn settle_pays_bettors_out_of_the_pool() {
+    let setup = setup(EdgeConfig::default()).await;
+    setup.add_app("1").await;
+    setup.add_app("2").await;
+    setup.bet("1", 30).await;
+    setup.bet("2", 10).await;
+
+    assert!(!setup.try_execute(EdgeOperation::Settle).await);
+
+    setup.advance(120);
+    setup.execute(EdgeOperation::Settle).await;
+
+    let pool = setup.pool().await;
+    assert!(pool < Amount::from_tokens(10_000));
+    assert_eq!(setup.balance(setup.owner).await, Amount::from_tokens(10).saturating_add(Amount::from_tokens(10_000).saturating_sub(pool)));
+    setup.assert_supply_balanced().await;
+
+    let settlement = setup.query("query { getSettlement(epoch: 0) { apps { appId rank } payouts { appId } } }").await;
+    let apps = settlement["getSettlement"]["apps"].as_array().unwrap();
+    assert_eq!(apps[0]["appId"], "1");
+    assert_eq!(apps[0]["rank"], 1);
+    assert_eq!(apps[1]["appId"], "2");
+    assert_eq!(settlement["getSettlement"]["payouts"].as_array().unwrap().len(), 2);
+
+    let top = setup.query("query { getTopApps(limit: 2) { appId rank } }").await;
+    assert_eq!(top["getTopApps"][0]["appId"], "1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn removing_an_application_refunds_its_bettors() {
+    let setup = setup(EdgeConfig::default()).await;
+    setup.add_app("1").await;
+    setup.bet("1", 20).await;
+
+    setup.execute(EdgeOperation::RemoveApplication { app_id: "1".to_string() }).await;
+
+    assert_eq!(setup.balance(setup.owner).await, Amount::from_tokens(50));
+    let apps = setup.query("query { getAllAppsForBetting { appId } }").await;
+    assert!(apps["getAllAppsForBetting"].as_array().unwrap().is_empty());
+    assert!(!setup.try_execute(EdgeOperation::Bet { app_id: "1".to_string(), amount: Amount::from_tokens(1) }).await);
+    setup.assert_supply_balanced().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inject_pool_adds_to_the_pool() {
+    let setup = setup(EdgeConfig::default()).await;
+
+    setup.execute(EdgeOperation::InjectPool { amount: Amount::from_tokens(500) }).await;
+
+    assert_eq!(setup.pool().await, Amount::from_tokens(10_500));
+    setup.assert_supply_balanced().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn owner_holds_every_role() {
+    let setup = setup(EdgeConfig::default()).await;
+
+    let owner = setup.query("query { getOwner }").await;
+    assert_eq!(owner["getOwner"], Value::String(setup.owner.to_string()));
+    for role in ["CURATOR", "KEEPER", "TREASURER"] {
+        let response = setup.query(&format!("query {{ hasRole(address: \"{}\", role: {role}) }}", setup.owner)).await;
+        assert_eq!(response["hasRole"], Value::Bool(true));
+    }
+
+    setup.execute(EdgeOperation::GrantRole { address: setup.owner, role: Role::Keeper }).await;
+    let roles = setup.query(&format!("query {{ getRoles(address: \"{}\") }}", setup.owner)).await;
+    assert_eq!(roles["getRoles"], serde_json::json!(["KEEPER"]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn roles_limit_what_each_account_may_do() {
+    let setup = setup(EdgeConfig::default()).await;
+    setup.add_app("1").await;
+    let keeper = setup.signed_by(AccountSecretKey::Ed25519(Ed25519SecretKey::generate()));
+    let add_app = || EdgeOperation::AddApplication { app_id: "2".to_string(), name: "App".to_string(), description: String::new() };
+    let remove_app = || EdgeOperation::RemoveApplication { app_id: "1".to_string() };
+    let inject = || EdgeOperation::InjectPool { amount: Amount::from_tokens(1) };
+    let is_whitelisted = format!("query {{ isWhitelisted(address: \"{}\") }}", keeper.owner);
+
+    // Settlement is due, so every rejection below comes from the missing role.
+    setup.advance(120);
+    assert!(!keeper.try_execute(EdgeOperation::Settle).await);
+    assert!(!keeper.try_execute(add_app()).await);
+    assert!(!keeper.try_execute(remove_app()).await);
+    assert!(!keeper.try_execute(inject()).await);
+    assert!(!keeper.try_execute(EdgeOperation::GrantRole { address: keeper.owner, role: Role::Keeper }).await);
+    assert_eq!(setup.query(&is_whitelisted).await["isWhitelisted"], Value::Bool(false));
+
+    setup.execute(EdgeOperation::GrantRole { address: keeper.owner, role: Role::Keeper }).await;
+    assert_eq!(setup.query(&is_whitelisted).await["isWhitelisted"], Value::Bool(true));
+    assert!(!keeper.try_execute(add_app()).await);
+    assert!(!keeper.try_execute(remove_app()).await);
+    assert!(!keeper.try_execute(inject()).await);
+    assert!(!keeper.try_execute(EdgeOperation::GrantRole { address: keeper.owner, role: Role::Curator }).await);
+    assert!(keeper.try_execute(EdgeOperation::Settle).await);
+
+    setup.execute(EdgeOperation::RevokeRole { address: keeper.owner, role: Role::Keeper }).await;
+    setup.advance(120);
+    assert!(!keeper.try_execute(EdgeOperation::Settle).await);
+    assert_eq!(setup.query(&is_whitelisted).await["isWhitelisted"], Value::Bool(false));
+    assert!(setup.try_execute(EdgeOperation::Settle).await);
+}
@@ -36,16 +36,13 @@ class SettlementKeeper {
         try {
             this.log('Sending settlement request...');
             const mutation = `
-            mutation($caller: AccountOwner!) {
-                settle(caller: $caller)
+            mutation {
+                settle
             }
             `;
             
             const payload = {
-                query: mutation,
-                variables: {
-                    caller: this.keeperAccount
-                }
+                query: mutation
             };
             
             const headers = { 'Content-Type': 'application/json' };
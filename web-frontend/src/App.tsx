@@ -5,6 +5,7 @@ import { ApolloClient, InMemoryCache, ApolloProvider, createHttpLink } from '@ap
 import { DynamicContextProvider } from '@dynamic-labs/sdk-react-core'
 import { EthereumWalletConnectors } from '@dynamic-labs/ethereum'
 import Header from './components/Header'
+import ClaimPointsBanner from './components/ClaimPointsBanner'
 import Dashboard from './pages/Dashboard'
 import Betting from './pages/Betting'
 import Rankings from './pages/Rankings'
@@ -76,6 +77,7 @@ function App() {
               <div className="min-h-screen bg-gray-50">
                 <Header />
                 <main className="container mx-auto px-4">
+                  <ClaimPointsBanner />
                   <Routes>
                     <Route path="/" element={<Dashboard />} />
                     <Route path="/betting" element={<Betting />} />
@@ -0,0 +1,49 @@
+import React, { useState } from 'react'
+import { Gift, Loader2 } from 'lucide-react'
+import { useLinera } from '../context/LineraContext'
+import { useTranslation } from 'react-i18next'
+
+const ClaimPointsBanner: React.FC = () => {
+  const { isConnected, isRegistered, register } = useLinera()
+  const { t } = useTranslation()
+  const [isClaiming, setIsClaiming] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  if (!isConnected || isRegistered) {
+    return null
+  }
+
+  const handleClaim = async () => {
+    setIsClaiming(true)
+    setError(null)
+    try {
+      await register()
+    } catch (error) {
+      setError(t('claimPointsFailed'))
+    } finally {
+      setIsClaiming(false)
+    }
+  }
+
+  return (
+    <div className="mt-6 bg-blue-50 border border-blue-200 rounded-lg p-4 flex flex-col sm:flex-row sm:items-center sm:justify-between gap-3">
+      <div className="flex items-center space-x-3">
+        <Gift className="h-6 w-6 text-blue-600 flex-shrink-0" />
+        <div>
+          <p className="text-sm text-blue-900">{t('claimPointsMessage')}</p>
+          {error && <p className="text-sm text-red-600 mt-1">{error}</p>}
+        </div>
+      </div>
+      <button
+        onClick={handleClaim}
+        disabled={isClaiming}
+        className="px-4 py-2 bg-blue-600 text-white text-sm font-medium rounded-md hover:bg-blue-700 transition-colors disabled:opacity-50 whitespace-nowrap flex items-center justify-center"
+      >
+        {isClaiming && <Loader2 className="h-4 w-4 animate-spin mr-2" />}
+        {isClaiming ? t('claimingPoints') : t('claimPoints')}
+      </button>
+    </div>
+  )
+}
+
+export default ClaimPointsBanner
@@ -1,12 +1,14 @@
 import React, { createContext, useContext, useState, ReactNode, useEffect } from 'react'
 import { useDynamicContext, DynamicConnectButton } from '@dynamic-labs/sdk-react-core'
-import { useQuery } from '@apollo/client'
-import { GET_USER_BALANCE } from '../services/graphql'
+import { useMutation, useQuery } from '@apollo/client'
+import { GET_ACCOUNT, REGISTER } from '../services/graphql'
 
 interface LineraContextType {
   isConnected: boolean
   account: string | null
   balance: number
+  isRegistered: boolean
+  register: () => Promise<void>
   connectWallet: () => void
   disconnectWallet: () => void
   isConnecting: boolean
@@ -27,6 +29,7 @@ export const LineraProvider: React.FC<LineraProviderProps> = ({ children }) => {
   const [isConnected, setIsConnected] = useState(false)
   const [account, setAccount] = useState<string | null>(null)
   const [balance, setBalance] = useState(0)
+  const [isRegistered, setIsRegistered] = useState(false)
 
   const formatAccountAddress = (addr: string) => {
     return addr.startsWith('0x') ? addr : `0x${addr}`
@@ -35,7 +38,7 @@ export const LineraProvider: React.FC<LineraProviderProps> = ({ children }) => {
   const {
     data: balanceData,
     refetch: refetchBalance
-  } = useQuery(GET_USER_BALANCE, {
+  } = useQuery(GET_ACCOUNT, {
     variables: {
       owner: account ? formatAccountAddress(account) : null
     },
@@ -66,6 +69,7 @@ export const LineraProvider: React.FC<LineraProviderProps> = ({ children }) => {
       setIsConnected(false)
       setAccount(null)
       setBalance(0)
+      setIsRegistered(false)
       setIsOwner(false)
       
       localStorage.removeItem('linera_wallet_connected')
@@ -74,11 +78,19 @@ export const LineraProvider: React.FC<LineraProviderProps> = ({ children }) => {
   }, [primaryWallet])
 
   useEffect(() => {
-    if (balanceData && balanceData.getBalance !== undefined) {
-      setBalance(balanceData.getBalance)
+    if (balanceData && balanceData.getAccount) {
+      setBalance(balanceData.getAccount.balance)
+      setIsRegistered(balanceData.getAccount.isRegistered)
     }
   }, [balanceData])
 
+  const [registerMutation] = useMutation(REGISTER)
+
+  const register = async () => {
+    await registerMutation()
+    await refetchBalance()
+  }
+
   const connectWallet = () => {
     setIsConnecting(true)
     setError(null)
@@ -112,6 +124,8 @@ export const LineraProvider: React.FC<LineraProviderProps> = ({ children }) => {
     isConnected,
     account,
     balance,
+    isRegistered,
+    register,
     connectWallet,
     disconnectWallet,
     isConnecting,
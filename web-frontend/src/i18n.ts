@@ -102,6 +102,10 @@ i18n
           "welcomeMessage": "预测 dApp 排名，赢取丰厚奖励！基于 Linera 区块链的去中心化预测平台。",
           "connectWallet": "请先连接钱包",
           "connectWalletMessage": "连接钱包后开始投注和参与排名预测",
+          "claimPoints": "领取积分",
+          "claimingPoints": "领取中...",
+          "claimPointsMessage": "领取初始积分后即可开始投注，每个账户只能领取一次",
+          "claimPointsFailed": "领取积分失败，请重试",
           "startBetting": "开始投注",
           "dappRanking": "dApp 排名",
           "viewAll": "查看全部 →",
@@ -339,6 +343,10 @@ i18n
           "welcomeMessage": "Predict dApp rankings and earn rewards! A decentralized prediction platform on Linera.",
           "connectWallet": "Connect Wallet to Start",
           "connectWalletMessage": "Connect your Linera wallet to claim your starting points and begin.",
+          "claimPoints": "Claim Points",
+          "claimingPoints": "Claiming...",
+          "claimPointsMessage": "Claim your starting points to begin allocating. Each account can claim once.",
+          "claimPointsFailed": "Failed to claim points, please try again",
           "startBetting": "Start Allocating",
           "dappRanking": "dApp Rankings",
           "viewAll": "View All →",
@@ -139,7 +139,6 @@ const AppManagement: React.FC = () => {
     try {
       const result = await addApplication({
         variables: {
-          caller: formatAccountOwner(account),
           appId: newApp.appId,
           name: newApp.name,
           description: newApp.description || '',
@@ -172,7 +171,6 @@ const AppManagement: React.FC = () => {
     try {
       const result = await removeApplication({
         variables: { 
-          caller: formatAccountOwner(account!),
           appId 
         }
       })
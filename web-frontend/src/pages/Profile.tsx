@@ -1,7 +1,7 @@
 import React, { useEffect, useState } from 'react'
 import { useLinera } from '../context/LineraContext'
 import { useQuery } from '@apollo/client'
-import { GET_USER_BETS, GET_USER_BALANCE, GET_ALL_APPS, UserBet } from '../services/graphql'
+import { GET_USER_BETS, GET_ACCOUNT, GET_ALL_APPS, UserBet } from '../services/graphql'
 import { Loader2 } from 'lucide-react'
 import { useTranslation } from 'react-i18next'
 import { formatNumber } from '../utils/formatters'
@@ -29,7 +29,7 @@ const Profile: React.FC = () => {
     skip: !isConnected || !account
   })
   
-  const { data: balanceData, loading: balanceLoading } = useQuery(GET_USER_BALANCE, {
+  const { data: accountData, loading: balanceLoading } = useQuery(GET_ACCOUNT, {
     variables: { owner: account },
     skip: !isConnected || !account
   })
@@ -64,7 +64,7 @@ const Profile: React.FC = () => {
   const maxBet = userBets.length > 0 ? Math.max(...userBets.map(bet => Number(bet.amount))) : 0
   const avgBet = userBets.length > 0 ? totalBetAmount / userBets.length : 0
   
-  const displayBalance = balanceData?.getBalance || balance || 0
+  const displayBalance = accountData?.getAccount?.balance || balance || 0
 
   return (
     <div className="space-y-6">
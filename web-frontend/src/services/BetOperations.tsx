@@ -1,7 +1,7 @@
 import { useCallback, useEffect, useState, useRef } from 'react';
 import { useQuery, useMutation, useSubscription } from '@apollo/client';
 import {
-  GET_USER_BALANCE,
+  GET_ACCOUNT,
   GET_USER_BETS,
   IS_WHITELISTED,
   GET_TOP_APPS,
@@ -143,7 +143,7 @@ const useBetOperations = ({
   }, [betEventsError]);
 
   const { data: userBalanceData, loading: userBalanceLoading, error: userBalanceError, refetch: refetchUserBalance } = useQuery(
-    GET_USER_BALANCE,
+    GET_ACCOUNT,
     {
       variables: { owner: currentAccount ? formatAccountOwner(currentAccount) : null },
       skip: !currentIsConnected || !currentAccount,
@@ -336,7 +336,6 @@ const useBetOperations = ({
     try {
       await placeBet({
         variables: {
-          caller: formatAccountOwner(currentAccount),
           appId,
           amount: amount.toString()
         }
@@ -355,7 +354,6 @@ const useBetOperations = ({
     try {
       await redeemBet({
         variables: {
-          caller: formatAccountOwner(currentAccount),
           appId,
           amount: amount.toString()
         }
@@ -374,7 +372,6 @@ const useBetOperations = ({
     try {
       await addApplication({
         variables: {
-          caller: formatAccountOwner(currentAccount),
           appId,
           name,
           description
@@ -394,7 +391,6 @@ const useBetOperations = ({
     try {
       await removeApplication({
         variables: {
-          caller: formatAccountOwner(currentAccount),
           appId
         }
       });
@@ -412,7 +408,6 @@ const useBetOperations = ({
     try {
       await injectPool({
         variables: {
-          caller: formatAccountOwner(currentAccount),
           amount: amount.toString()
         }
       });
@@ -6,9 +6,18 @@ export const SUBSCRIBE_BET_EVENTS = gql`
   }
 `;
 
-export const GET_USER_BALANCE = gql`
-  query GetBalance($owner: AccountOwner) {
-    getBalance(owner: $owner)
+export const GET_ACCOUNT = gql`
+  query GetAccount($owner: AccountOwner!) {
+    getAccount(owner: $owner) {
+      balance
+      isRegistered
+    }
+  }
+`;
+
+export const REGISTER = gql`
+  mutation Register {
+    register
   }
 `;
 
@@ -135,14 +144,14 @@ export const GET_APP_INFO = gql`
 `;
 
 export const ADD_APPLICATION = gql`
-  mutation AddApplication($caller: AccountOwner!, $appId: String!, $name: String!, $description: String!) {
-    addApplication(caller: $caller, appId: $appId, name: $name, description: $description)
+  mutation AddApplication($appId: String!, $name: String!, $description: String!) {
+    addApplication(appId: $appId, name: $name, description: $description)
   }
 `;
 
 export const REMOVE_APPLICATION = gql`
-  mutation RemoveApplication($caller: AccountOwner!, $appId: String!) {
-    removeApplication(caller: $caller, appId: $appId)
+  mutation RemoveApplication($appId: String!) {
+    removeApplication(appId: $appId)
   }
 `;
 
@@ -153,20 +162,20 @@ export const IS_USER_WHITELISTED = gql`
 `;
 
 export const PLACE_BET = gql`
-  mutation PlaceBet($caller: AccountOwner!, $appId: String!, $amount: String!) {
-    placeBet(caller: $caller, appId: $appId, amount: $amount)
+  mutation PlaceBet($appId: String!, $amount: String!) {
+    placeBet(appId: $appId, amount: $amount)
   }
 `;
 
 export const REDEEM_BET = gql`
-  mutation RedeemBet($caller: AccountOwner!, $appId: String!, $amount: String!) {
-    redeemBet(caller: $caller, appId: $appId, amount: $amount)
+  mutation RedeemBet($appId: String!, $amount: String!) {
+    redeemBet(appId: $appId, amount: $amount)
   }
 `;
 
 export const INJECT_POOL = gql`
-  mutation InjectPool($caller: AccountOwner!, $amount: String!) {
-    injectPool(caller: $caller, amount: $amount)
+  mutation InjectPool($amount: String!) {
+    injectPool(amount: $amount)
   }
 `;
 